@@ -1,13 +1,16 @@
 //! Multi-provider AI client for file analysis and cleanup recommendations.
 //!
-//! Supports OpenAI, Google Gemini, Anthropic Claude, and Moonshot Kimi.
-//! Each provider has its own request/response format handled transparently.
+//! Supports OpenAI, Google Gemini, Anthropic Claude, Moonshot Kimi, Ollama
+//! and OpenAI-compatible servers (llama.cpp, vLLM, LM Studio). Each protocol
+//! has its own request/response format handled transparently; see
+//! `ai_providers` for how a provider's endpoint is resolved.
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use crate::ai_prompts;
+use crate::ai_providers::{Connection, Protocol};
 use crate::filesystem::FileInfo;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,9 +26,13 @@ pub struct AIAnalysis {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CleanupRecommendation {
     pub id: String,
+    /// Set from the kind of the recommendation's candidates.
+    #[serde(default)]
     pub category: String,
     pub title: String,
     pub description: String,
+    /// Sum of the item sizes, computed from the candidates.
+    #[serde(default)]
     pub space_reclaimable: u64,
     pub risk_level: String,
     pub items: Vec<CleanupItem>,
@@ -33,39 +40,44 @@ pub struct CleanupRecommendation {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CleanupItem {
+    /// ID of the scan candidate this item was built from; the model gives
+    /// only this, path and size are filled in from the candidate.
+    #[serde(default)]
+    pub candidate_id: String,
+    #[serde(default)]
     pub path: String,
+    #[serde(default)]
     pub size: u64,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub selected: bool,
 }
 
 pub struct AIClient {
     client: Client,
-    provider: String,
-    api_key: String,
+    protocol: Protocol,
+    api_key: Option<String>,
     model: String,
-    base_url: String,
+    url: String,
 }
 
 impl AIClient {
-    pub fn new(provider: &str, api_key: &str, model: &str) -> Self {
-        let base_url = match provider {
-            "openai" => "https://api.openai.com/v1/chat/completions".to_string(),
-            "gemini" => format!(
-                "https://generativelanguage.googleapis.com\
-                 /v1beta/models/{model}:generateContent"
-            ),
-            "claude" => "https://api.anthropic.com/v1/messages".to_string(),
-            "kimi" => "https://api.moonshot.cn/v1/chat/completions".to_string(),
-            _ => "https://api.openai.com/v1/chat/completions".to_string(),
+    pub fn new(connection: Connection, model: &str) -> Self {
+        let base = &connection.base_url;
+        let url = match connection.provider.protocol {
+            Protocol::OpenAi => format!("{base}/chat/completions"),
+            Protocol::Gemini => format!("{base}/models/{model}:generateContent"),
+            Protocol::Claude => format!("{base}/messages"),
+            Protocol::Ollama => format!("{base}/api/chat"),
         };
 
         AIClient {
             client: Client::new(),
-            provider: provider.to_string(),
-            api_key: api_key.to_string(),
+            protocol: connection.provider.protocol,
+            api_key: connection.api_key,
             model: model.to_string(),
-            base_url,
+            url,
         }
     }
 
@@ -96,11 +108,11 @@ impl AIClient {
     }
 
     async fn send_message(&self, prompt: &str) -> Result<String, String> {
-        match self.provider.as_str() {
-            "openai" | "kimi" => self.send_openai_compatible(prompt).await,
-            "gemini" => self.send_gemini(prompt).await,
-            "claude" => self.send_claude(prompt).await,
-            _ => Err(format!("Unsupported provider: {}", self.provider)),
+        match self.protocol {
+            Protocol::OpenAi => self.send_openai_compatible(prompt).await,
+            Protocol::Gemini => self.send_gemini(prompt).await,
+            Protocol::Claude => self.send_claude(prompt).await,
+            Protocol::Ollama => self.send_ollama(prompt).await,
         }
     }
 
@@ -113,10 +125,7 @@ impl AIClient {
             "temperature": 0.3,
         });
 
-        let resp = self
-            .client
-            .post(&self.base_url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
+        let resp = authorized(self.client.post(&self.url), self.api_key.as_deref())
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
@@ -131,7 +140,11 @@ impl AIClient {
     }
 
     async fn send_gemini(&self, prompt: &str) -> Result<String, String> {
-        let url = format!("{}?key={}", self.base_url, self.api_key);
+        let url = format!(
+            "{}?key={}",
+            self.url,
+            self.api_key.as_deref().unwrap_or_default()
+        );
         let body = json!({
             "contents": [{"parts": [{"text": prompt}]}],
             "generationConfig": {"temperature": 0.3}
@@ -165,8 +178,8 @@ impl AIClient {
 
         let resp = self
             .client
-            .post(&self.base_url)
-            .header("x-api-key", &self.api_key)
+            .post(&self.url)
+            .header("x-api-key", self.api_key.as_deref().unwrap_or_default())
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .json(&body)
@@ -180,9 +193,45 @@ impl AIClient {
             .map(|s| s.to_string())
             .ok_or_else(|| "No content in response".to_string())
     }
+
+    async fn send_ollama(&self, prompt: &str) -> Result<String, String> {
+        let body = json!({
+            "model": self.model,
+            "messages": [
+                {"role": "user", "content": prompt}
+            ],
+            "stream": false,
+            "options": {"temperature": 0.3},
+        });
+
+        let resp = authorized(self.client.post(&self.url), self.api_key.as_deref())
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {e}"))?;
+
+        let json = handle_response(resp).await?;
+        json["message"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "No content in response".to_string())
+    }
+}
+
+/// Adds a bearer token when there is a key; local servers often run without
+/// authentication.
+pub fn authorized(
+    request: reqwest::RequestBuilder,
+    api_key: Option<&str>,
+) -> reqwest::RequestBuilder {
+    match api_key {
+        Some(key) => request.header("Authorization", format!("Bearer {key}")),
+        None => request,
+    }
 }
 
-async fn handle_response(response: reqwest::Response) -> Result<Value, String> {
+pub async fn handle_response(response: reqwest::Response) -> Result<Value, String> {
     if !response.status().is_success() {
         let status = response.status();
         let text = response
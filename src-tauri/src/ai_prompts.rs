@@ -43,18 +43,19 @@ pub fn parse_file_analysis(response: &str, file_path: &str) -> Result<AIAnalysis
 
 pub fn build_recommendations_prompt(system_stats: &str) -> String {
     format!(
-        "Based on these system statistics, provide cleanup \
-         recommendations. Respond with ONLY valid JSON \
+        "Based on these system statistics and cleanup candidates, provide \
+         cleanup recommendations. Respond with ONLY valid JSON \
          (no markdown, no code blocks):\n{system_stats}\n\n\
+         Only recommend items from the candidates list, referencing each by \
+         its id. Do not invent paths or sizes. Each recommendation must \
+         contain candidates of a single kind.\n\
          Respond with a JSON array of recommendations:\n\
          [{{\"id\": \"<unique-id>\", \
-         \"category\": \"<category>\", \
          \"title\": \"<short title>\", \
          \"description\": \"<what to clean>\", \
-         \"space_reclaimable\": <bytes>, \
          \"risk_level\": \"<low|medium|high>\", \
-         \"items\": [{{\"path\": \"<path>\", \
-         \"size\": <bytes>, \"description\": \"<why>\", \
+         \"items\": [{{\"candidate_id\": \"<candidate id>\", \
+         \"description\": \"<why>\", \
          \"selected\": <true|false>}}]}}]"
     )
 }
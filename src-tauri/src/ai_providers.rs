@@ -0,0 +1,215 @@
+//! AI provider registry and per-provider endpoint settings.
+//!
+//! Cloud providers (OpenAI, Gemini, Claude, Kimi) have fixed endpoints and
+//! need an API key from the keyring. Local providers — Ollama, a llama.cpp
+//! server, and any OpenAI-compatible server such as vLLM or LM Studio — run
+//! wherever the user points them: their base URL and model are settings and
+//! the API key is optional, so they work without a keyring at all.
+
+use serde::{Deserialize, Serialize};
+
+use crate::credentials;
+use crate::database::Database;
+
+/// Wire format spoken by a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// `POST {base}/chat/completions`, models from `GET {base}/models`.
+    OpenAi,
+    Gemini,
+    Claude,
+    /// `POST {base}/api/chat`, models from `GET {base}/api/tags`.
+    Ollama,
+}
+
+#[derive(Debug)]
+pub struct ProviderSpec {
+    pub id: &'static str,
+    pub protocol: Protocol,
+    /// Runs on a server the user controls; the base URL is configurable and
+    /// the API key optional.
+    pub local: bool,
+    pub default_base_url: &'static str,
+    /// Used when no model is requested or saved. Local providers have none:
+    /// which models exist depends on the server.
+    pub default_model: Option<&'static str>,
+}
+
+const PROVIDERS: &[ProviderSpec] = &[
+    ProviderSpec {
+        id: "openai",
+        protocol: Protocol::OpenAi,
+        local: false,
+        default_base_url: "https://api.openai.com/v1",
+        default_model: Some("gpt-4o-mini"),
+    },
+    ProviderSpec {
+        id: "gemini",
+        protocol: Protocol::Gemini,
+        local: false,
+        default_base_url: "https://generativelanguage.googleapis.com/v1beta",
+        default_model: Some("gemini-2.0-flash"),
+    },
+    ProviderSpec {
+        id: "claude",
+        protocol: Protocol::Claude,
+        local: false,
+        default_base_url: "https://api.anthropic.com/v1",
+        default_model: Some("claude-sonnet-4-20250514"),
+    },
+    ProviderSpec {
+        id: "kimi",
+        protocol: Protocol::OpenAi,
+        local: false,
+        default_base_url: "https://api.moonshot.cn/v1",
+        default_model: Some("moonshot-v1-8k"),
+    },
+    ProviderSpec {
+        id: "ollama",
+        protocol: Protocol::Ollama,
+        local: true,
+        default_base_url: "http://localhost:11434",
+        default_model: None,
+    },
+    ProviderSpec {
+        id: "llamacpp",
+        protocol: Protocol::OpenAi,
+        local: true,
+        default_base_url: "http://localhost:8080/v1",
+        default_model: None,
+    },
+    ProviderSpec {
+        id: "openai-compatible",
+        protocol: Protocol::OpenAi,
+        local: true,
+        default_base_url: "http://localhost:8000/v1",
+        default_model: None,
+    },
+];
+
+pub fn spec(provider: &str) -> Result<&'static ProviderSpec, String> {
+    PROVIDERS
+        .iter()
+        .find(|p| p.id == provider)
+        .ok_or_else(|| format!("Unsupported AI provider: {provider}"))
+}
+
+/// Saved endpoint of a provider, stored as JSON under [`endpoint_key`].
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProviderEndpoint {
+    /// Overrides the provider's default base URL; local providers only.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Where to reach a provider and how to authenticate.
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub provider: &'static ProviderSpec,
+    /// Without a trailing slash.
+    pub base_url: String,
+    pub api_key: Option<String>,
+}
+
+pub fn endpoint_key(provider: &str) -> String {
+    format!("ai_endpoint.{provider}")
+}
+
+pub fn load_endpoint(db: &Database, provider: &str) -> ProviderEndpoint {
+    db.get_setting(&endpoint_key(provider))
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+/// Validates and saves `endpoint`. Empty strings clear a field.
+pub fn save_endpoint(
+    db: &Database,
+    provider: &str,
+    endpoint: ProviderEndpoint,
+) -> Result<(), String> {
+    let spec = spec(provider)?;
+    let endpoint = ProviderEndpoint {
+        base_url: non_empty(endpoint.base_url),
+        model: non_empty(endpoint.model),
+    };
+    if let Some(url) = &endpoint.base_url {
+        if !spec.local {
+            return Err(format!("The endpoint of {provider} cannot be changed"));
+        }
+        validate_base_url(url)?;
+    }
+    let value = serde_json::to_string(&endpoint).map_err(|e| format!("Serialize error: {e}"))?;
+    db.set_setting(&endpoint_key(provider), &value)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn validate_base_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid base URL {url}: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Base URL must use http or https: {url}"));
+    }
+    if parsed.host_str().is_none() || parsed.query().is_some() {
+        return Err(format!("Base URL must be a plain server address: {url}"));
+    }
+    Ok(())
+}
+
+/// Resolves how to reach `provider`. `base_url` and `api_key` override the
+/// saved base URL and the keyring, so an endpoint can be tried before it is
+/// saved. Cloud providers fail without a key; for local providers a missing
+/// key, or a keyring that is unavailable, means no authentication.
+pub fn connection(
+    db: &Database,
+    provider: &str,
+    base_url: Option<String>,
+    api_key: Option<String>,
+) -> Result<Connection, String> {
+    let spec = spec(provider)?;
+    let base_url = if spec.local {
+        match non_empty(base_url).or_else(|| load_endpoint(db, provider).base_url) {
+            Some(url) => {
+                validate_base_url(&url)?;
+                url
+            }
+            None => spec.default_base_url.to_string(),
+        }
+    } else {
+        spec.default_base_url.to_string()
+    };
+    let api_key = match non_empty(api_key) {
+        Some(key) => Some(key),
+        None => match credentials::get_credential(provider) {
+            Ok(key) => non_empty(Some(key)),
+            Err(_) if spec.local => None,
+            Err(_) => {
+                return Err(format!(
+                    "No API key saved for {provider}. Configure it in Settings."
+                ))
+            }
+        },
+    };
+    Ok(Connection {
+        provider: spec,
+        base_url: base_url.trim_end_matches('/').to_string(),
+        api_key,
+    })
+}
+
+/// The model to use: `requested`, else the saved model, else the provider's
+/// default.
+pub fn model(db: &Database, provider: &str, requested: Option<String>) -> Result<String, String> {
+    let spec = spec(provider)?;
+    non_empty(requested)
+        .or_else(|| load_endpoint(db, provider).model)
+        .or_else(|| spec.default_model.map(str::to_string))
+        .ok_or_else(|| format!("No model selected for {provider}. Choose one in Settings."))
+}
@@ -0,0 +1,299 @@
+//! Developer project artifact sweeper.
+//!
+//! Finds per-project build output and dependency directories (`node_modules`,
+//! `target`, `build`, `.venv`, ...) under a root. A directory only counts as
+//! an artifact when its parent holds the marker file of a matching project
+//! type, so a hand-written `build/` folder in an unrelated tree is left alone,
+//! and when git tracks no file inside it, so committed sources under `build/`
+//! (icons, entitlements, scripts) are never swept. Git is asked once per
+//! repository for its tracked files.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cleaners::measure_cleanup;
+use crate::filesystem::{modified_timestamp, walk_dir};
+use crate::path_safety::{self, PathPolicy};
+use crate::system::{dir_size_and_count, CleanupResult};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectArtifact {
+    pub path: String,
+    pub project: String,
+    /// Artifact directory name, e.g. `node_modules`.
+    pub kind: String,
+    pub size: u64,
+    /// Unix timestamp of the newest project file outside its artifacts;
+    /// `None` when the project could not be fully read.
+    pub last_touched: Option<i64>,
+}
+
+const PYTHON_MARKERS: &[&str] = &[
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+    "requirements.txt",
+    "Pipfile",
+];
+const GRADLE_MARKERS: &[&str] = &[
+    "build.gradle",
+    "build.gradle.kts",
+    "settings.gradle",
+    "settings.gradle.kts",
+];
+
+/// Artifact directory names and the marker files that identify their project.
+/// An empty marker list means "a sibling `.py` file".
+const ARTIFACT_KINDS: &[(&str, &[&str])] = &[
+    ("node_modules", &["package.json"]),
+    ("target", &["Cargo.toml", "pom.xml"]),
+    (
+        "build",
+        &[
+            "package.json",
+            "pyproject.toml",
+            "setup.py",
+            "build.gradle",
+            "build.gradle.kts",
+            "CMakeLists.txt",
+        ],
+    ),
+    ("dist", &["package.json", "pyproject.toml", "setup.py"]),
+    (".next", &["package.json"]),
+    (".venv", PYTHON_MARKERS),
+    ("venv", PYTHON_MARKERS),
+    (".tox", &["tox.ini", "pyproject.toml", "setup.py"]),
+    (".gradle", GRADLE_MARKERS),
+    ("__pycache__", &[]),
+];
+
+/// Returns the artifact kind of `path`, if it is one.
+pub fn artifact_kind(path: &Path) -> Option<&'static str> {
+    kind_of(path, &GitTracked::default())
+}
+
+fn kind_of(path: &Path, tracked: &GitTracked) -> Option<&'static str> {
+    marked_artifact_kind(path).filter(|_| !tracked.contains_files(path))
+}
+
+/// Artifact kind of `path` by its name and project marker alone, without
+/// asking git; enough for walks that only skip artifacts.
+pub fn marked_artifact_kind(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    let parent = path.parent()?;
+    let (kind, markers) = ARTIFACT_KINDS.iter().find(|(k, _)| *k == name)?;
+    if !path.is_dir() || path.is_symlink() {
+        return None;
+    }
+    let matched = if markers.is_empty() {
+        has_python_sources(parent)
+    } else {
+        markers.iter().any(|m| parent.join(m).exists())
+    };
+    matched.then_some(*kind)
+}
+
+/// Directories holding git-tracked files, listed once per repository.
+#[derive(Default)]
+struct GitTracked {
+    /// Repository top level -> every directory with a tracked file below it.
+    repos: Mutex<HashMap<PathBuf, HashSet<PathBuf>>>,
+}
+
+impl GitTracked {
+    /// Whether git tracks any file under `dir`. Outside a repository, or
+    /// without git, nothing is tracked.
+    fn contains_files(&self, dir: &Path) -> bool {
+        let Some(top) = dir.ancestors().skip(1).find(|a| a.join(".git").exists()) else {
+            return false;
+        };
+        let mut repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+        repos
+            .entry(top.to_path_buf())
+            .or_insert_with(|| tracked_dirs(top))
+            .contains(dir)
+    }
+}
+
+fn tracked_dirs(top: &Path) -> HashSet<PathBuf> {
+    let Ok(out) = Command::new("git")
+        .arg("-C")
+        .arg(top)
+        .args(["ls-files", "-z"])
+        .output()
+    else {
+        return HashSet::new();
+    };
+    if !out.status.success() {
+        return HashSet::new();
+    }
+    let mut dirs = HashSet::new();
+    for file in out.stdout.split(|b| *b == 0).filter(|f| !f.is_empty()) {
+        let file = top.join(String::from_utf8_lossy(file).as_ref());
+        for dir in file.ancestors().skip(1) {
+            if dir == top || !dirs.insert(dir.to_path_buf()) {
+                break;
+            }
+        }
+    }
+    dirs
+}
+
+fn has_python_sources(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.path().extension().is_some_and(|ext| ext == "py"))
+        })
+        .unwrap_or(false)
+}
+
+/// Walks `root` and returns every artifact directory with its kind, without
+/// descending into artifacts or other hidden directories.
+pub fn artifact_dirs(root: &Path) -> Vec<(PathBuf, &'static str)> {
+    find_artifact_dirs(root, &GitTracked::default())
+}
+
+fn find_artifact_dirs(root: &Path, tracked: &GitTracked) -> Vec<(PathBuf, &'static str)> {
+    let mut found = Vec::new();
+    walk_dir(root, |entry| {
+        if !entry.file_type().is_dir() || entry.depth() == 0 {
+            return true;
+        }
+        if let Some(kind) = kind_of(entry.path(), tracked) {
+            found.push((entry.path().to_path_buf(), kind));
+            return false;
+        }
+        !is_hidden(entry.path())
+    });
+    found
+}
+
+/// Finds project artifacts under `root`, largest first. `kinds` restricts the
+/// result to the given directory names.
+pub fn find_project_artifacts(
+    root: &str,
+    kinds: Option<&[String]>,
+) -> Result<Vec<ProjectArtifact>, String> {
+    let root_path = Path::new(root);
+    if !root_path.is_dir() {
+        return Err(format!("Not a directory: {root}"));
+    }
+
+    let tracked = GitTracked::default();
+    let dirs: Vec<(PathBuf, &str)> = find_artifact_dirs(root_path, &tracked)
+        .into_iter()
+        .filter(|(_, kind)| kinds.is_none_or(|ks| ks.iter().any(|k| k == kind)))
+        .collect();
+
+    let mut artifacts: Vec<ProjectArtifact> = dirs
+        .par_iter()
+        .map(|(path, kind)| {
+            let project = path.parent().unwrap_or(path);
+            ProjectArtifact {
+                path: path.to_string_lossy().to_string(),
+                project: project.to_string_lossy().to_string(),
+                kind: kind.to_string(),
+                size: dir_size_and_count(path).0,
+                last_touched: project_last_touched(project, &tracked),
+            }
+        })
+        .collect();
+    artifacts.sort_by_key(|a| std::cmp::Reverse(a.size));
+    Ok(artifacts)
+}
+
+/// Removes the given artifact directories after re-checking each one.
+pub fn clean_project_artifacts(paths: &[String]) -> Result<CleanupResult, String> {
+    let targets: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    if let Some(bad) = targets.iter().find(|p| artifact_kind(p).is_none()) {
+        return Err(format!("Not a project artifact: {}", bad.display()));
+    }
+    let policy = PathPolicy::new(&path_safety::user_roots());
+    measure_cleanup(&targets, || {
+        let mut removed = 0;
+        let mut messages = Vec::new();
+        for path in &targets {
+            match policy.remove(path) {
+                Ok(_) => removed += 1,
+                Err(e) => messages.push(e),
+            }
+        }
+        messages.insert(
+            0,
+            format!("Removed {removed} of {} artifacts", targets.len()),
+        );
+        Ok(CleanupResult {
+            success: removed > 0,
+            space_freed: 0,
+            message: messages.join("; "),
+        })
+    })
+}
+
+/// Removes the artifacts of every project under `root` that has not been
+/// touched for `days` days. Projects whose last activity cannot be read are
+/// skipped.
+pub fn sweep_inactive_projects(
+    root: &str,
+    days: u64,
+    kinds: Option<&[String]>,
+) -> Result<CleanupResult, String> {
+    let cutoff = chrono::Utc::now().timestamp() - (days as i64) * 86_400;
+    let stale: Vec<String> = find_project_artifacts(root, kinds)?
+        .into_iter()
+        .filter(|a| a.last_touched.is_some_and(|t| t < cutoff))
+        .map(|a| a.path)
+        .collect();
+    if stale.is_empty() {
+        return Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: format!("No projects inactive for {days} days"),
+        });
+    }
+    clean_project_artifacts(&stale)
+}
+
+/// Newest mtime in `project`, skipping artifact directories and VCS internals
+/// other than the files git touches on commit and checkout. `None` when part
+/// of the project is unreadable or no file has a usable mtime.
+fn project_last_touched(project: &Path, tracked: &GitTracked) -> Option<i64> {
+    let mut newest = ["index", "HEAD"]
+        .iter()
+        .filter_map(|f| fs::metadata(project.join(".git").join(f)).ok())
+        .map(|m| modified_timestamp(&m))
+        .max()
+        .unwrap_or(0);
+
+    let mut metadata_read = true;
+    let walked = walk_dir(project, |entry| {
+        if entry.depth() > 0
+            && entry.file_type().is_dir()
+            && (is_hidden(entry.path()) || kind_of(entry.path(), tracked).is_some())
+        {
+            return false;
+        }
+        if entry.file_type().is_file() {
+            match entry.metadata() {
+                Ok(meta) => newest = newest.max(modified_timestamp(&meta)),
+                Err(_) => metadata_read = false,
+            }
+        }
+        true
+    });
+    (walked && metadata_read && newest > 0).then_some(newest)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+}
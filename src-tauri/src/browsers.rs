@@ -0,0 +1,762 @@
+//! Browser installation and profile discovery.
+//!
+//! Browsers are looked up in their native, Flatpak (`~/.var/app`) and Snap
+//! (`~/snap`) locations. Chromium-family profiles come from the `Local State`
+//! file and Firefox-family profiles from `profiles.ini`, so renamed and
+//! non-default profiles are found wherever the browser keeps them.
+//!
+//! Cleanable data is split into [`DataCategory`] values, each mapped to a
+//! fixed set of directories per browser family. Running browsers are
+//! detected through their profile lock files so that caches are not removed
+//! underneath them.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cleaners::{measure_cleanup, remove_dir_contents};
+use crate::system::{dir_size_and_count, CleanupResult, PackageCacheInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserFamily {
+    Chromium,
+    Firefox,
+}
+
+/// Where one packaging of a browser keeps its profiles and caches, relative
+/// to the home directory.
+struct InstallLocation {
+    variant: &'static str,
+    config_root: &'static str,
+    cache_root: &'static str,
+}
+
+struct BrowserDef {
+    name: &'static str,
+    family: BrowserFamily,
+    /// Executables as seen in `/proc/<pid>/exe`, matched against the
+    /// trailing components of its path: a bare name matches the file name,
+    /// `google/chrome/chrome` only Google Chrome's install.
+    binaries: &'static [&'static str],
+    locations: &'static [InstallLocation],
+}
+
+const fn loc(
+    variant: &'static str,
+    config_root: &'static str,
+    cache_root: &'static str,
+) -> InstallLocation {
+    InstallLocation {
+        variant,
+        config_root,
+        cache_root,
+    }
+}
+
+const BROWSERS: &[BrowserDef] = &[
+    BrowserDef {
+        name: "Google Chrome",
+        family: BrowserFamily::Chromium,
+        binaries: &["google/chrome/chrome", "app/extra/chrome", "google-chrome"],
+        locations: &[
+            loc("native", ".config/google-chrome", ".cache/google-chrome"),
+            loc(
+                "flatpak",
+                ".var/app/com.google.Chrome/config/google-chrome",
+                ".var/app/com.google.Chrome/cache/google-chrome",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "Chromium",
+        family: BrowserFamily::Chromium,
+        binaries: &[
+            "chromium",
+            "chromium-browser",
+            "chromium/chrome",
+            "chromium-browser/chrome",
+        ],
+        locations: &[
+            loc("native", ".config/chromium", ".cache/chromium"),
+            loc(
+                "flatpak",
+                ".var/app/org.chromium.Chromium/config/chromium",
+                ".var/app/org.chromium.Chromium/cache/chromium",
+            ),
+            loc(
+                "snap",
+                "snap/chromium/common/chromium",
+                "snap/chromium/common/.cache/chromium",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "Brave",
+        family: BrowserFamily::Chromium,
+        binaries: &["brave", "brave-browser"],
+        locations: &[
+            loc(
+                "native",
+                ".config/BraveSoftware/Brave-Browser",
+                ".cache/BraveSoftware/Brave-Browser",
+            ),
+            loc(
+                "flatpak",
+                ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser",
+                ".var/app/com.brave.Browser/cache/BraveSoftware/Brave-Browser",
+            ),
+            loc(
+                "snap",
+                "snap/brave/current/.config/BraveSoftware/Brave-Browser",
+                "snap/brave/common/.cache/BraveSoftware/Brave-Browser",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "Microsoft Edge",
+        family: BrowserFamily::Chromium,
+        binaries: &["msedge", "microsoft-edge"],
+        locations: &[
+            loc("native", ".config/microsoft-edge", ".cache/microsoft-edge"),
+            loc(
+                "flatpak",
+                ".var/app/com.microsoft.Edge/config/microsoft-edge",
+                ".var/app/com.microsoft.Edge/cache/microsoft-edge",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "Vivaldi",
+        family: BrowserFamily::Chromium,
+        binaries: &["vivaldi-bin", "vivaldi"],
+        locations: &[
+            loc("native", ".config/vivaldi", ".cache/vivaldi"),
+            loc(
+                "flatpak",
+                ".var/app/com.vivaldi.Vivaldi/config/vivaldi",
+                ".var/app/com.vivaldi.Vivaldi/cache/vivaldi",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "Opera",
+        family: BrowserFamily::Chromium,
+        binaries: &["opera"],
+        locations: &[
+            loc("native", ".config/opera", ".cache/opera"),
+            loc(
+                "flatpak",
+                ".var/app/com.opera.Opera/config/opera",
+                ".var/app/com.opera.Opera/cache/opera",
+            ),
+            loc(
+                "snap",
+                "snap/opera/current/.config/opera",
+                "snap/opera/common/.cache/opera",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "Firefox",
+        family: BrowserFamily::Firefox,
+        binaries: &["firefox", "firefox-bin"],
+        locations: &[
+            loc("native", ".mozilla/firefox", ".cache/mozilla/firefox"),
+            loc(
+                "native",
+                ".config/mozilla/firefox",
+                ".cache/mozilla/firefox",
+            ),
+            loc(
+                "flatpak",
+                ".var/app/org.mozilla.firefox/.mozilla/firefox",
+                ".var/app/org.mozilla.firefox/cache/mozilla/firefox",
+            ),
+            loc(
+                "snap",
+                "snap/firefox/common/.mozilla/firefox",
+                "snap/firefox/common/.cache/mozilla/firefox",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "LibreWolf",
+        family: BrowserFamily::Firefox,
+        binaries: &["librewolf", "librewolf-bin"],
+        locations: &[
+            loc("native", ".librewolf", ".cache/librewolf"),
+            loc(
+                "flatpak",
+                ".var/app/io.gitlab.librewolf-community/.librewolf",
+                ".var/app/io.gitlab.librewolf-community/cache/librewolf",
+            ),
+        ],
+    },
+    BrowserDef {
+        name: "Waterfox",
+        family: BrowserFamily::Firefox,
+        binaries: &["waterfox", "waterfox-bin"],
+        locations: &[
+            loc("native", ".waterfox", ".cache/waterfox"),
+            loc(
+                "flatpak",
+                ".var/app/net.waterfox.waterfox/.waterfox",
+                ".var/app/net.waterfox.waterfox/cache/waterfox",
+            ),
+        ],
+    },
+];
+
+/// One installed packaging of a browser.
+#[derive(Debug, Clone)]
+pub struct BrowserInstall {
+    /// Name shown in the UI, e.g. `Firefox (Flatpak)`.
+    pub display_name: String,
+    pub family: BrowserFamily,
+    pub binaries: &'static [&'static str],
+    pub config_root: PathBuf,
+    pub cache_root: PathBuf,
+    /// Flatpak or Snap: pids in its lock files belong to a sandbox pid
+    /// namespace and cannot be looked up directly.
+    pub sandboxed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct BrowserProfile {
+    /// Directory name (Chromium) or `profiles.ini` path (Firefox).
+    pub id: String,
+    pub name: String,
+    pub data_dir: PathBuf,
+    pub cache_dir: PathBuf,
+}
+
+/// Finds every browser installation present for the current user.
+pub fn discover_installs() -> Vec<BrowserInstall> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut installs: Vec<BrowserInstall> = Vec::new();
+    for def in BROWSERS {
+        for location in def.locations {
+            let config_root = home.join(location.config_root);
+            let cache_root = home.join(location.cache_root);
+            if !config_root.exists() && !cache_root.exists() {
+                continue;
+            }
+            // Firefox has two native layouts sharing a cache root; prefer the
+            // one whose profile directory actually exists.
+            let other_layout_exists = def.locations.iter().any(|l| {
+                l.variant == location.variant
+                    && l.config_root != location.config_root
+                    && home.join(l.config_root).exists()
+            });
+            if !config_root.exists() && other_layout_exists {
+                continue;
+            }
+            let display_name = match location.variant {
+                "native" => def.name.to_string(),
+                "flatpak" => format!("{} (Flatpak)", def.name),
+                _ => format!("{} (Snap)", def.name),
+            };
+            if installs.iter().any(|i| i.display_name == display_name) {
+                continue;
+            }
+            installs.push(BrowserInstall {
+                display_name,
+                family: def.family,
+                binaries: def.binaries,
+                config_root,
+                cache_root,
+                sandboxed: location.variant != "native",
+            });
+        }
+    }
+    installs
+}
+
+pub fn find_install(display_name: &str) -> Result<BrowserInstall, String> {
+    discover_installs()
+        .into_iter()
+        .find(|i| i.display_name == display_name)
+        .ok_or_else(|| format!("Unknown browser: {display_name}"))
+}
+
+impl BrowserInstall {
+    pub fn profiles(&self) -> Vec<BrowserProfile> {
+        match self.family {
+            BrowserFamily::Chromium => self.chromium_profiles(),
+            BrowserFamily::Firefox => self.firefox_profiles(),
+        }
+    }
+
+    /// Reads `Local State` → `profile.info_cache`, falling back to scanning for
+    /// `Default` / `Profile N` directories in the config and cache roots.
+    fn chromium_profiles(&self) -> Vec<BrowserProfile> {
+        let mut names: HashMap<String, String> =
+            fs::read_to_string(self.config_root.join("Local State"))
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .and_then(|v| v["profile"]["info_cache"].as_object().cloned())
+                .map(|cache| {
+                    cache
+                        .into_iter()
+                        .map(|(id, info)| {
+                            let name = info["name"].as_str().unwrap_or(&id).to_string();
+                            (id, name)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+        for root in [&self.config_root, &self.cache_root] {
+            let Ok(entries) = fs::read_dir(root) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let id = entry.file_name().to_string_lossy().to_string();
+                if entry.path().is_dir() && (id == "Default" || id.starts_with("Profile ")) {
+                    names.entry(id.clone()).or_insert(id);
+                }
+            }
+        }
+
+        // Opera keeps a single profile directly in its root directory.
+        if names.is_empty() && self.config_root.join("Preferences").exists() {
+            return vec![BrowserProfile {
+                id: String::new(),
+                name: "Default".to_string(),
+                data_dir: self.config_root.clone(),
+                cache_dir: self.cache_root.clone(),
+            }];
+        }
+
+        let mut profiles: Vec<BrowserProfile> = names
+            .into_iter()
+            .map(|(id, name)| BrowserProfile {
+                data_dir: self.config_root.join(&id),
+                cache_dir: self.cache_root.join(&id),
+                id,
+                name,
+            })
+            .collect();
+        profiles.sort_by(|a, b| a.id.cmp(&b.id));
+        profiles
+    }
+
+    /// Reads `[ProfileN]` sections of `profiles.ini`, falling back to any
+    /// directory in the cache root.
+    fn firefox_profiles(&self) -> Vec<BrowserProfile> {
+        let ini = fs::read_to_string(self.config_root.join("profiles.ini")).unwrap_or_default();
+        let mut profiles: Vec<BrowserProfile> = parse_ini(&ini)
+            .into_iter()
+            .filter(|(section, _)| section.starts_with("Profile"))
+            .filter_map(|(_, keys)| {
+                let path = keys.get("Path")?;
+                let relative = keys.get("IsRelative").is_none_or(|v| v == "1");
+                let (data_dir, cache_dir) = if relative {
+                    (self.config_root.join(path), self.cache_root.join(path))
+                } else {
+                    let base = Path::new(path).file_name()?;
+                    (PathBuf::from(path), self.cache_root.join(base))
+                };
+                Some(BrowserProfile {
+                    id: path.clone(),
+                    name: keys.get("Name").cloned().unwrap_or_else(|| path.clone()),
+                    data_dir,
+                    cache_dir,
+                })
+            })
+            .collect();
+
+        if profiles.is_empty() {
+            if let Ok(entries) = fs::read_dir(&self.cache_root) {
+                for entry in entries.flatten().filter(|e| e.path().is_dir()) {
+                    let id = entry.file_name().to_string_lossy().to_string();
+                    profiles.push(BrowserProfile {
+                        name: id.clone(),
+                        data_dir: self.config_root.join(&id),
+                        cache_dir: entry.path(),
+                        id,
+                    });
+                }
+            }
+        }
+        profiles.sort_by(|a, b| a.id.cmp(&b.id));
+        profiles
+    }
+
+    /// Lock symlinks a running browser holds: Chromium's `SingletonLock`
+    /// (`host-pid`) and Firefox's per-profile `lock` (`ip:+pid`).
+    fn lock_files(&self) -> Vec<PathBuf> {
+        match self.family {
+            BrowserFamily::Chromium => vec![self.config_root.join("SingletonLock")],
+            BrowserFamily::Firefox => self
+                .profiles()
+                .iter()
+                .map(|p| p.data_dir.join("lock"))
+                .collect(),
+        }
+    }
+
+    /// Whether this install is currently running.
+    ///
+    /// A lock symlink must exist; it counts when its pid is a live process of
+    /// this browser or, for sandboxed installs whose pids are namespaced, when
+    /// any process of this browser is running. Locks left behind by a crash
+    /// are ignored.
+    pub fn is_running(&self) -> bool {
+        let pids: Vec<Option<u32>> = self
+            .lock_files()
+            .iter()
+            .filter_map(|lock| fs::read_link(lock).ok())
+            .map(|target| lock_pid(&target.to_string_lossy()))
+            .collect();
+        if pids.is_empty() {
+            return false;
+        }
+        if pids
+            .iter()
+            .flatten()
+            .any(|pid| process_matches(*pid, self.binaries))
+        {
+            return true;
+        }
+        self.sandboxed && running_pids(self.binaries).next().is_some()
+    }
+
+    /// Directories of `category` that belong to `profile`.
+    pub fn profile_paths(&self, profile: &BrowserProfile, category: DataCategory) -> Vec<PathBuf> {
+        let (data, cache) = (&profile.data_dir, &profile.cache_dir);
+        let paths = match (self.family, category) {
+            (BrowserFamily::Chromium, DataCategory::Cache) => vec![cache.join("Cache")],
+            (BrowserFamily::Chromium, DataCategory::CodeCache) => {
+                vec![cache.join("Code Cache"), data.join("Code Cache")]
+            }
+            (BrowserFamily::Chromium, DataCategory::GpuCache) => {
+                vec![data.join("GPUCache"), data.join("DawnCache")]
+            }
+            (BrowserFamily::Chromium, DataCategory::ServiceWorker) => vec![
+                data.join("Service Worker/CacheStorage"),
+                data.join("Service Worker/ScriptCache"),
+            ],
+            (BrowserFamily::Chromium, DataCategory::SiteData) => vec![
+                data.join("IndexedDB"),
+                data.join("File System"),
+                data.join("blob_storage"),
+            ],
+            (BrowserFamily::Firefox, DataCategory::Cache) => vec![cache.join("cache2")],
+            (BrowserFamily::Firefox, DataCategory::StartupCache) => {
+                vec![cache.join("startupCache")]
+            }
+            (BrowserFamily::Firefox, DataCategory::Thumbnails) => vec![cache.join("thumbnails")],
+            (BrowserFamily::Firefox, DataCategory::ShaderCache) => vec![cache.join("shader-cache")],
+            (BrowserFamily::Firefox, DataCategory::CrashReports) => vec![data.join("minidumps")],
+            (BrowserFamily::Firefox, DataCategory::SiteData) => vec![data.join("storage/default")],
+            _ => Vec::new(),
+        };
+        paths.into_iter().filter(|p| p.exists()).collect()
+    }
+
+    /// Directories of `category` shared by all profiles of the install.
+    pub fn shared_paths(&self, category: DataCategory) -> Vec<PathBuf> {
+        let root = &self.config_root;
+        let paths = match (self.family, category) {
+            (BrowserFamily::Chromium, DataCategory::ShaderCache) => vec![
+                root.join("ShaderCache"),
+                root.join("GrShaderCache"),
+                root.join("GraphiteDawnCache"),
+            ],
+            (BrowserFamily::Chromium, DataCategory::CrashReports) => {
+                vec![root.join("Crash Reports")]
+            }
+            // Reports in `pending` have not been uploaded yet and are kept.
+            (BrowserFamily::Chromium, DataCategory::Crashpad) => {
+                vec![root.join("Crashpad/completed")]
+            }
+            (BrowserFamily::Firefox, DataCategory::CrashReports) => {
+                vec![root.join("Crash Reports/submitted")]
+            }
+            _ => Vec::new(),
+        };
+        paths.into_iter().filter(|p| p.exists()).collect()
+    }
+
+    /// Every existing directory of `category`, across all profiles.
+    pub fn category_paths(&self, category: DataCategory) -> Vec<PathBuf> {
+        let mut paths = self.shared_paths(category);
+        for profile in self.profiles() {
+            paths.extend(self.profile_paths(&profile, category));
+        }
+        paths
+    }
+
+    /// Disk and code cache directories of every profile.
+    pub fn cache_dirs(&self) -> Vec<PathBuf> {
+        DataCategory::DEFAULT_CLEAN
+            .iter()
+            .flat_map(|c| self.category_paths(*c))
+            .collect()
+    }
+
+    /// Size of each category this browser family has, including empty ones.
+    pub fn category_sizes(&self) -> Vec<CategorySize> {
+        self.sizes_by_category(|c| self.category_paths(c))
+    }
+
+    /// Per-category sizes of a single profile; shared data is not included.
+    pub fn profile_category_sizes(&self, profile: &BrowserProfile) -> Vec<CategorySize> {
+        self.sizes_by_category(|c| self.profile_paths(profile, c))
+    }
+
+    fn sizes_by_category(&self, paths: impl Fn(DataCategory) -> Vec<PathBuf>) -> Vec<CategorySize> {
+        DataCategory::ALL
+            .iter()
+            .filter(|c| c.applies_to(self.family))
+            .map(|c| CategorySize {
+                category: *c,
+                size: sum_sizes(&paths(*c)),
+                default_selected: DataCategory::DEFAULT_CLEAN.contains(c),
+            })
+            .collect()
+    }
+
+    pub fn find_profile(&self, id: &str) -> Result<BrowserProfile, String> {
+        self.profiles()
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("Unknown {} profile: {id}", self.display_name))
+    }
+}
+
+/// Per-profile breakdown for the UI.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BrowserProfileInfo {
+    /// Install display name, as accepted by `clean_browser_cache`.
+    pub browser: String,
+    pub profile_id: String,
+    /// Name from `Local State` / `profiles.ini`, e.g. "Work".
+    pub name: String,
+    pub path: String,
+    pub categories: Vec<CategorySize>,
+    pub total_size: u64,
+    pub running: bool,
+}
+
+pub fn get_browser_profiles() -> Result<Vec<BrowserProfileInfo>, String> {
+    let mut result = Vec::new();
+    for install in discover_installs() {
+        let running = install.is_running();
+        for profile in install.profiles() {
+            let categories = install.profile_category_sizes(&profile);
+            result.push(BrowserProfileInfo {
+                browser: install.display_name.clone(),
+                profile_id: profile.id.clone(),
+                name: profile.name.clone(),
+                path: profile.data_dir.to_string_lossy().to_string(),
+                total_size: categories.iter().map(|c| c.size).sum(),
+                categories,
+                running,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Browser data that can be cleaned. Cookies, history, saved logins and
+/// preferences are deliberately not representable here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataCategory {
+    Cache,
+    CodeCache,
+    GpuCache,
+    ServiceWorker,
+    ShaderCache,
+    StartupCache,
+    Thumbnails,
+    CrashReports,
+    /// Crash dumps Crashpad has finished with; pending uploads are kept.
+    Crashpad,
+    /// IndexedDB and per-site storage. Signs users out of some web apps, so
+    /// it is never selected by default.
+    SiteData,
+}
+
+impl DataCategory {
+    pub const ALL: [DataCategory; 10] = [
+        DataCategory::Cache,
+        DataCategory::CodeCache,
+        DataCategory::GpuCache,
+        DataCategory::ServiceWorker,
+        DataCategory::ShaderCache,
+        DataCategory::StartupCache,
+        DataCategory::Thumbnails,
+        DataCategory::CrashReports,
+        DataCategory::Crashpad,
+        DataCategory::SiteData,
+    ];
+
+    /// What `clean_browser_cache` removes when no categories are given.
+    pub const DEFAULT_CLEAN: [DataCategory; 2] = [DataCategory::Cache, DataCategory::CodeCache];
+
+    fn applies_to(self, family: BrowserFamily) -> bool {
+        match self {
+            DataCategory::CodeCache
+            | DataCategory::GpuCache
+            | DataCategory::ServiceWorker
+            | DataCategory::Crashpad => family == BrowserFamily::Chromium,
+            DataCategory::StartupCache | DataCategory::Thumbnails => {
+                family == BrowserFamily::Firefox
+            }
+            _ => true,
+        }
+    }
+}
+
+/// What to do when asked to clean a browser that is running.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunningPolicy {
+    /// Fail with an error asking the user to close the browser.
+    #[default]
+    Refuse,
+    /// Clean anyway and say so in the result.
+    Warn,
+    /// Clean once the browser has exited.
+    Queue,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CategorySize {
+    pub category: DataCategory,
+    pub size: u64,
+    pub default_selected: bool,
+}
+
+/// Files that hold cookies, history, logins or keys. Cleaning refuses any
+/// directory that contains one of them directly.
+const PROTECTED_FILES: &[&str] = &[
+    "Cookies",
+    "History",
+    "Login Data",
+    "Web Data",
+    "Preferences",
+    "cookies.sqlite",
+    "places.sqlite",
+    "logins.json",
+    "key4.db",
+    "prefs.js",
+];
+
+fn sum_sizes(paths: &[PathBuf]) -> u64 {
+    paths.iter().map(|p| dir_size_and_count(p).0).sum()
+}
+
+/// Empties the directories of `categories` for the given install.
+/// `profile` limits cleaning to one profile (shared data is then skipped).
+pub fn clean_categories(
+    install: &BrowserInstall,
+    profile: Option<&BrowserProfile>,
+    categories: &[DataCategory],
+) -> Result<CleanupResult, String> {
+    let dirs: Vec<PathBuf> = categories
+        .iter()
+        .flat_map(|c| match profile {
+            Some(p) => install.profile_paths(p, *c),
+            None => install.category_paths(*c),
+        })
+        .collect();
+
+    if dirs.is_empty() {
+        return Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: format!("No cache found for {}", install.display_name),
+        });
+    }
+    if let Some(bad) = dirs
+        .iter()
+        .find(|d| PROTECTED_FILES.iter().any(|f| d.join(f).exists()))
+    {
+        return Err(format!(
+            "Refusing to clean {}: it contains browser profile data",
+            bad.display()
+        ));
+    }
+
+    let roots = [install.config_root.clone(), install.cache_root.clone()];
+    measure_cleanup(&dirs, || {
+        let mut cleaned = false;
+        let mut messages: Vec<String> = Vec::new();
+
+        for dir in dirs.iter().filter(|d| d.exists()) {
+            match remove_dir_contents(dir, &roots) {
+                Ok(_) => {
+                    cleaned = true;
+                    messages.push(format!("Cleaned {}", dir.display()));
+                }
+                Err(e) => {
+                    messages.push(format!("Failed to clean {}: {e}", dir.display()));
+                }
+            }
+        }
+
+        Ok(CleanupResult {
+            success: cleaned,
+            space_freed: 0,
+            message: messages.join("; "),
+        })
+    })
+}
+
+/// Trailing pid of a lock target such as `myhost-4242` or `127.0.1.1:+4242`.
+fn lock_pid(target: &str) -> Option<u32> {
+    let digits = target.len() - target.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    target[target.len() - digits..].parse().ok()
+}
+
+fn process_matches(pid: u32, binaries: &[&str]) -> bool {
+    fs::read_link(format!("/proc/{pid}/exe"))
+        .is_ok_and(|exe| binaries.iter().any(|b| exe.ends_with(b)))
+}
+
+/// Pids of processes whose executable is one of `binaries`.
+fn running_pids<'a>(binaries: &'a [&'a str]) -> impl Iterator<Item = u32> + 'a {
+    fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+        .filter(move |pid| process_matches(*pid, binaries))
+}
+
+/// Minimal INI reader: `section -> key -> value`.
+fn parse_ini(content: &str) -> Vec<(String, HashMap<String, String>)> {
+    let mut sections: Vec<(String, HashMap<String, String>)> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.to_string(), HashMap::new()));
+        } else if let Some((key, value)) = line.split_once('=') {
+            if let Some((_, keys)) = sections.last_mut() {
+                keys.insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    sections
+}
+
+pub fn get_browser_caches() -> Result<Vec<PackageCacheInfo>, String> {
+    Ok(discover_installs()
+        .into_iter()
+        .map(|install| {
+            let dirs = install.cache_dirs();
+            PackageCacheInfo {
+                manager: install.display_name.clone(),
+                path: install.cache_root.to_string_lossy().to_string(),
+                size: sum_sizes(&dirs),
+                exists: true,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,223 @@
+//! Per-entry audit of `~/.cache`.
+//!
+//! Lists each top-level entry with its size and last-modified time, labels
+//! well-known entries and rates how safe they are to remove. A top-level
+//! directory with known subdirectories, such as `nvidia`, is listed by its
+//! children instead (`nvidia/GLCache`), so only those are rated. Everything in
+//! `~/.cache` is regenerable by definition (XDG), but some entries are
+//! expensive to rebuild or belong to applications that may be running.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::cleaners::measure_cleanup;
+use crate::filesystem::modified_timestamp;
+use crate::path_safety::PathPolicy;
+use crate::system::{dir_size_and_count, CleanupResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Safety {
+    /// Rebuilt transparently on demand.
+    Safe,
+    /// Regenerable, but slow to rebuild or used by a running application.
+    Caution,
+    /// Not recognised; review before removing.
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheEntry {
+    /// Path relative to `~/.cache`.
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    /// Unix timestamp of the newest file in the entry.
+    pub modified_at: i64,
+    pub description: Option<String>,
+    pub safety: Safety,
+}
+
+/// Known `~/.cache` entries: name relative to `~/.cache`, description and
+/// safety rating.
+const KNOWN_ENTRIES: &[(&str, &str, Safety)] = &[
+    ("thumbnails", "File manager thumbnails", Safety::Safe),
+    ("fontconfig", "Font configuration cache", Safety::Safe),
+    ("mesa_shader_cache", "Mesa GPU shader cache", Safety::Safe),
+    (
+        "mesa_shader_cache_db",
+        "Mesa GPU shader cache",
+        Safety::Safe,
+    ),
+    ("nvidia/GLCache", "NVIDIA OpenGL shader cache", Safety::Safe),
+    ("nvidia/ComputeCache", "NVIDIA CUDA JIT cache", Safety::Safe),
+    (
+        "radv_builtin_shaders64",
+        "RADV Vulkan shader cache",
+        Safety::Safe,
+    ),
+    ("pip", "pip download cache", Safety::Safe),
+    ("yarn", "Yarn package cache", Safety::Safe),
+    ("go-build", "Go build cache", Safety::Safe),
+    ("uv", "uv package cache", Safety::Safe),
+    ("pypoetry", "Poetry cache and virtualenvs", Safety::Caution),
+    (
+        "JetBrains",
+        "JetBrains IDE caches and indexes",
+        Safety::Caution,
+    ),
+    ("google-chrome", "Google Chrome disk cache", Safety::Caution),
+    ("chromium", "Chromium disk cache", Safety::Caution),
+    ("mozilla", "Firefox disk cache", Safety::Caution),
+    ("BraveSoftware", "Brave disk cache", Safety::Caution),
+    ("tracker3", "GNOME search index", Safety::Caution),
+    ("gnome-software", "GNOME Software metadata", Safety::Safe),
+    ("flatpak", "Flatpak metadata cache", Safety::Safe),
+    ("electron", "Electron download cache", Safety::Safe),
+    (
+        "ms-playwright",
+        "Playwright browser binaries",
+        Safety::Caution,
+    ),
+    (
+        "huggingface",
+        "Hugging Face models and datasets",
+        Safety::Caution,
+    ),
+    ("torch", "PyTorch model cache", Safety::Caution),
+    ("sccache", "sccache compiler cache", Safety::Safe),
+    ("ccache", "ccache compiler cache", Safety::Safe),
+    ("Homebrew", "Homebrew download cache", Safety::Safe),
+];
+
+pub fn cache_dir() -> PathBuf {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".cache"))
+}
+
+/// Lists every top-level entry of `~/.cache`, largest first. Entries with
+/// known subdirectories are replaced by their children.
+pub fn audit_cache_dir() -> Result<Vec<CacheEntry>, String> {
+    let root = cache_dir();
+    let entries: Vec<PathBuf> = fs::read_dir(&root)
+        .map_err(|e| format!("Failed to read {}: {e}", root.display()))?
+        .flatten()
+        .flat_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let nested = KNOWN_ENTRIES
+                .iter()
+                .any(|(n, _, _)| n.starts_with(&format!("{name}/")));
+            match fs::read_dir(e.path()) {
+                Ok(children) if nested && !e.path().is_symlink() => {
+                    children.flatten().map(|c| c.path()).collect()
+                }
+                _ => vec![e.path()],
+            }
+        })
+        .collect();
+
+    let mut audited: Vec<CacheEntry> = entries.par_iter().map(|p| audit_entry(&root, p)).collect();
+    audited.sort_by_key(|e| std::cmp::Reverse(e.size));
+    Ok(audited)
+}
+
+fn audit_entry(root: &Path, path: &Path) -> CacheEntry {
+    let name = path
+        .strip_prefix(root)
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let known = KNOWN_ENTRIES.iter().find(|(n, _, _)| *n == name);
+    let size = if path.is_dir() && !path.is_symlink() {
+        dir_size_and_count(path).0
+    } else {
+        fs::symlink_metadata(path).map(|m| m.len()).unwrap_or(0)
+    };
+    CacheEntry {
+        path: path.to_string_lossy().to_string(),
+        size,
+        modified_at: newest_mtime(path),
+        description: known.map(|(_, d, _)| d.to_string()),
+        safety: known.map(|(_, _, s)| *s).unwrap_or(Safety::Unknown),
+        name,
+    }
+}
+
+fn newest_mtime(path: &Path) -> i64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| modified_timestamp(&m))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Removes the named entries of `~/.cache`, as listed by [`audit_cache_dir`].
+pub fn clean_cache_entries(names: &[String]) -> Result<CleanupResult, String> {
+    let root = cache_dir();
+    let mut targets = Vec::new();
+    for name in names {
+        let components: Vec<Component> = Path::new(name).components().collect();
+        if components.is_empty()
+            || components.len() > 2
+            || !components.iter().all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(format!("Invalid cache entry name: {name}"));
+        }
+        targets.push(root.join(name));
+    }
+    measure_cleanup(&targets, || remove_entries(&targets))
+}
+
+/// Removes every `~/.cache` entry not modified in `days` days. Only entries
+/// rated [`Safety::Safe`] are included unless `include_caution` or
+/// `include_unknown` adds the entries with those ratings.
+pub fn clean_stale_cache_entries(
+    days: u64,
+    include_caution: bool,
+    include_unknown: bool,
+) -> Result<CleanupResult, String> {
+    let cutoff = chrono::Utc::now().timestamp() - (days as i64) * 86_400;
+    let stale: Vec<String> = audit_cache_dir()?
+        .into_iter()
+        .filter(|e| e.modified_at < cutoff)
+        .filter(|e| match e.safety {
+            Safety::Safe => true,
+            Safety::Caution => include_caution,
+            Safety::Unknown => include_unknown,
+        })
+        .map(|e| e.name)
+        .collect();
+    if stale.is_empty() {
+        return Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: format!("No cache entries older than {days} days"),
+        });
+    }
+    clean_cache_entries(&stale)
+}
+
+fn remove_entries(targets: &[PathBuf]) -> Result<CleanupResult, String> {
+    let policy = PathPolicy::new(&[cache_dir()]);
+    let mut removed = 0;
+    let mut messages = Vec::new();
+    for path in targets {
+        match policy.remove(path) {
+            Ok(_) => removed += 1,
+            Err(e) => messages.push(e),
+        }
+    }
+    messages.insert(0, format!("Removed {removed} of {} entries", targets.len()));
+    Ok(CleanupResult {
+        success: removed > 0,
+        space_freed: 0,
+        message: messages.join("; "),
+    })
+}
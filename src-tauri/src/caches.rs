@@ -1,140 +1,288 @@
-//! Package manager and browser cache detection and cleanup.
+//! Package manager and toolchain cache detection and cleanup.
 //!
-//! Scans common cache directories for npm, pip, cargo, dnf, and popular
-//! browsers (Chrome, Brave, Firefox, Chromium).
+//! Registers the built-in cleaners for language toolchains (npm, yarn, pnpm,
+//! bun, pip, uv, poetry, conda, cargo, go, Gradle, Maven, sbt, ccache,
+//! sccache, Homebrew), system package managers (dnf, apt, pacman, zypper),
+//! Flatpak and Snap (see [`crate::sandboxed_apps`]).
 
 use std::fs;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 
+use walkdir::WalkDir;
+
+use crate::cargo_cache::CargoCleaner;
+use crate::cleaners::{measured_clean, CacheCleaner, CleanerRegistry, SpecCleaner};
+use crate::path_safety::PathPolicy;
+use crate::sandboxed_apps;
 use crate::system::{dir_size_and_count, CleanupResult, PackageCacheInfo};
 
-pub fn get_package_caches() -> Result<Vec<PackageCacheInfo>, String> {
+/// Builds the registry of built-in cleaners plus any user-declared ones.
+pub fn registry() -> CleanerRegistry {
     let home = dirs::home_dir().unwrap_or_default();
-    let caches = vec![
-        ("npm", home.join(".npm")),
-        ("pip", home.join(".cache/pip")),
-        ("cargo", home.join(".cargo/registry")),
-        ("dnf", "/var/cache/dnf".into()),
-    ];
+    let mut registry = CleanerRegistry::new();
+    for cleaner in builtin_cleaners(&home) {
+        registry.register(cleaner);
+    }
+    registry.load_user_definitions();
+    registry
+}
 
-    let results = caches
-        .into_iter()
-        .map(|(mgr, path)| {
-            let exists = path.exists();
-            let size = if exists {
-                dir_size_and_count(&path).0
-            } else {
-                0
-            };
-            PackageCacheInfo {
-                manager: mgr.to_string(),
-                path: path.to_string_lossy().to_string(),
-                size,
-                exists,
-            }
-        })
-        .collect();
+fn builtin_cleaners(home: &Path) -> Vec<Box<dyn CacheCleaner>> {
+    let h = |p: &str| home.join(p);
+    let gopath = std::env::var_os("GOPATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| h("go"));
+    let go_mod = std::env::var_os("GOMODCACHE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| gopath.join("pkg/mod"));
+    let go_build = std::env::var_os("GOCACHE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| h(".cache/go-build"));
+    // GOCACHE may point anywhere; its parent is the only root it implies.
+    let go_build_root = go_build
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| h(".cache"));
+    let conda_pkgs = [
+        "miniconda3",
+        "anaconda3",
+        "miniforge3",
+        "mambaforge",
+        ".conda",
+    ]
+    .iter()
+    .map(|root| h(root).join("pkgs"))
+    .collect();
 
-    Ok(results)
+    let mut cleaners: Vec<Box<dyn CacheCleaner>> = vec![
+        // JavaScript
+        Box::new(
+            SpecCleaner::command(
+                "npm",
+                vec![h(".npm/_cacache")],
+                &["npm", "cache", "clean", "--force"],
+            )
+            .within(vec![h(".npm")])
+            .with_dir_fallback(),
+        ),
+        Box::new(
+            SpecCleaner::dirs("yarn", vec![h(".cache/yarn"), h(".yarn/berry/cache")])
+                .within(vec![h(".cache"), h(".yarn")]),
+        ),
+        Box::new(SpecCleaner::command(
+            "pnpm",
+            vec![h(".local/share/pnpm/store")],
+            &["pnpm", "store", "prune"],
+        )),
+        Box::new(
+            SpecCleaner::command(
+                "bun",
+                vec![h(".bun/install/cache")],
+                &["bun", "pm", "cache", "rm"],
+            )
+            .within(vec![h(".bun")])
+            .with_dir_fallback(),
+        ),
+        // Python
+        Box::new(
+            SpecCleaner::command("pip", vec![h(".cache/pip")], &["pip", "cache", "purge"])
+                .with_dir_fallback(),
+        ),
+        Box::new(
+            SpecCleaner::command("uv", vec![h(".cache/uv")], &["uv", "cache", "clean"])
+                .with_dir_fallback(),
+        ),
+        // Only the download caches; ~/.cache/pypoetry/virtualenvs holds live environments.
+        Box::new(SpecCleaner::dirs(
+            "poetry",
+            vec![h(".cache/pypoetry/cache"), h(".cache/pypoetry/artifacts")],
+        )),
+        Box::new(SpecCleaner::command(
+            "conda",
+            conda_pkgs,
+            &["conda", "clean", "--all", "-y"],
+        )),
+        // Rust, Go, JVM, C/C++
+        Box::new(CargoCleaner),
+        // Module cache files are read-only, so only `go clean` can remove them.
+        Box::new(SpecCleaner::command(
+            "go-mod",
+            vec![go_mod],
+            &["go", "clean", "-modcache"],
+        )),
+        Box::new(
+            SpecCleaner::command("go-build", vec![go_build], &["go", "clean", "-cache"])
+                .within(vec![go_build_root])
+                .with_dir_fallback(),
+        ),
+        Box::new(SpecCleaner::dirs("gradle", vec![h(".gradle/caches")]).within(vec![h(".gradle")])),
+        Box::new(MavenCleaner {
+            repository: h(".m2/repository"),
+        }),
+        Box::new(
+            SpecCleaner::dirs(
+                "sbt",
+                vec![h(".ivy2/cache"), h(".sbt/boot"), h(".cache/coursier")],
+            )
+            .within(vec![h(".ivy2"), h(".sbt"), h(".cache")]),
+        ),
+        Box::new(
+            SpecCleaner::command(
+                "ccache",
+                vec![h(".cache/ccache"), h(".ccache")],
+                &["ccache", "-C"],
+            )
+            .within(vec![h(".cache"), h(".ccache")])
+            .with_dir_fallback(),
+        ),
+        Box::new(SpecCleaner::dirs("sccache", vec![h(".cache/sccache")])),
+        Box::new(
+            SpecCleaner::command(
+                "homebrew",
+                vec![h(".cache/Homebrew")],
+                &["brew", "cleanup", "--prune=all", "-s"],
+            )
+            .with_dir_fallback(),
+        ),
+        // System package managers
+        Box::new(
+            SpecCleaner::command(
+                "dnf",
+                vec!["/var/cache/dnf".into(), "/var/cache/libdnf5".into()],
+                &["dnf", "clean", "all"],
+            )
+            .root(),
+        ),
+        Box::new(
+            SpecCleaner::command(
+                "apt",
+                vec!["/var/cache/apt/archives".into()],
+                &["apt-get", "clean"],
+            )
+            .root(),
+        ),
+        Box::new(
+            SpecCleaner::command(
+                "pacman",
+                vec!["/var/cache/pacman/pkg".into()],
+                &["paccache", "-r"],
+            )
+            .root(),
+        ),
+        Box::new(
+            SpecCleaner::command(
+                "zypper",
+                vec!["/var/cache/zypp/packages".into()],
+                &["zypper", "clean", "--all"],
+            )
+            .root(),
+        ),
+    ];
+    // Sandboxed app platforms
+    cleaners.extend(sandboxed_apps::cleaners());
+    cleaners
 }
 
-pub fn clean_package_cache(manager: &str) -> Result<CleanupResult, String> {
-    match manager {
-        "npm" => clean_npm_cache(),
-        "pip" => clean_pip_cache(),
-        "cargo" => clean_cargo_cache(),
-        "dnf" => clean_dnf_cache(),
-        _ => Err(format!("Unknown package manager: {manager}")),
-    }
+/// Maven's local repository also holds artifacts installed with
+/// `mvn install`, which cannot be downloaded again. Only version directories
+/// whose `_remote.repositories` file names a remote repository for every
+/// file are removed.
+struct MavenCleaner {
+    repository: PathBuf,
 }
 
-fn clean_npm_cache() -> Result<CleanupResult, String> {
-    let output = Command::new("npm")
-        .args(["cache", "clean", "--force"])
-        .output()
-        .map_err(|e| format!("npm cache clean: {e}"))?;
-    Ok(CleanupResult {
-        success: output.status.success(),
-        space_freed: 0,
-        message: String::from_utf8_lossy(&output.stdout).to_string(),
-    })
+impl MavenCleaner {
+    fn downloaded_versions(&self) -> Vec<PathBuf> {
+        WalkDir::new(&self.repository)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && e.file_name() == "_remote.repositories")
+            .filter_map(|e| {
+                let content = fs::read_to_string(e.path()).ok()?;
+                let dir = e.path().parent()?.to_path_buf();
+                (is_downloaded(&content) && !dir.join("maven-metadata-local.xml").exists())
+                    .then_some(dir)
+            })
+            .collect()
+    }
 }
 
-fn clean_pip_cache() -> Result<CleanupResult, String> {
-    let home = dirs::home_dir().unwrap_or_default();
-    let cache_path = home.join(".cache/pip");
-    let size = if cache_path.exists() {
-        dir_size_and_count(&cache_path).0
-    } else {
-        0
-    };
-    let output = Command::new("pip")
-        .args(["cache", "purge"])
-        .output()
-        .map_err(|e| format!("pip cache purge: {e}"))?;
-    Ok(CleanupResult {
-        success: output.status.success(),
-        space_freed: size,
-        message: String::from_utf8_lossy(&output.stdout).to_string(),
-    })
+/// Whether every entry of a `_remote.repositories` file (`<file>><repo>=`)
+/// names a repository; `mvn install` leaves the repository empty.
+fn is_downloaded(remote_repositories: &str) -> bool {
+    let mut entries = remote_repositories
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .peekable();
+    entries.peek().is_some()
+        && entries.all(|l| {
+            l.split_once('>')
+                .and_then(|(_, repo)| repo.strip_suffix('='))
+                .is_some_and(|repo| !repo.is_empty())
+        })
 }
 
-fn clean_cargo_cache() -> Result<CleanupResult, String> {
-    let home = dirs::home_dir().unwrap_or_default();
-    let registry = home.join(".cargo/registry/cache");
-    let size = if registry.exists() {
-        dir_size_and_count(&registry).0
-    } else {
-        0
-    };
-    if registry.exists() {
-        fs::remove_dir_all(&registry).map_err(|e| format!("cargo cache clean: {e}"))?;
+impl CacheCleaner for MavenCleaner {
+    fn name(&self) -> &str {
+        "maven"
     }
-    Ok(CleanupResult {
-        success: true,
-        space_freed: size,
-        message: "Cargo registry cache cleaned".to_string(),
-    })
-}
 
-fn clean_dnf_cache() -> Result<CleanupResult, String> {
-    let output = Command::new("sudo")
-        .args(["dnf", "clean", "all"])
-        .output()
-        .map_err(|e| format!("dnf clean: {e}"))?;
-    Ok(CleanupResult {
-        success: output.status.success(),
-        space_freed: 0,
-        message: String::from_utf8_lossy(&output.stdout).to_string(),
-    })
-}
+    fn paths(&self) -> Vec<PathBuf> {
+        vec![self.repository.clone()]
+    }
 
-pub fn get_browser_caches() -> Result<Vec<PackageCacheInfo>, String> {
-    let home = dirs::home_dir().unwrap_or_default();
-    let caches = vec![
-        ("Google Chrome", home.join(".cache/google-chrome")),
-        ("Brave", home.join(".cache/BraveSoftware")),
-        ("Firefox", home.join(".cache/mozilla/firefox")),
-        ("Chromium", home.join(".cache/chromium")),
-    ];
+    fn size(&self) -> u64 {
+        self.downloaded_versions()
+            .iter()
+            .map(|d| dir_size_and_count(d).0)
+            .sum()
+    }
 
-    let results = caches
-        .into_iter()
-        .map(|(name, path)| {
-            let exists = path.exists();
-            let size = if exists {
-                dir_size_and_count(&path).0
-            } else {
-                0
-            };
-            PackageCacheInfo {
-                manager: name.to_string(),
-                path: path.to_string_lossy().to_string(),
-                size,
-                exists,
+    fn clean(&self) -> Result<CleanupResult, String> {
+        let versions = self.downloaded_versions();
+        let policy = PathPolicy::new(std::slice::from_ref(&self.repository));
+        let mut removed = 0;
+        let mut messages = Vec::new();
+        for dir in &versions {
+            match policy.remove(dir) {
+                Ok(_) => removed += 1,
+                Err(e) => messages.push(e),
             }
+        }
+        messages.insert(
+            0,
+            format!(
+                "Removed {removed} of {} downloaded artifact versions; locally installed ones were kept",
+                versions.len()
+            ),
+        );
+        Ok(CleanupResult {
+            success: removed == versions.len(),
+            space_freed: 0,
+            message: messages.join("; "),
         })
-        .collect();
+    }
+}
+
+pub fn get_package_caches() -> Result<Vec<PackageCacheInfo>, String> {
+    Ok(registry().iter().map(|c| c.info()).collect())
+}
+
+pub fn clean_package_cache(manager: &str) -> Result<CleanupResult, String> {
+    let registry = registry();
+    let cleaner = registry
+        .get(manager)
+        .ok_or_else(|| format!("Unknown package manager: {manager}"))?;
+    measured_clean(cleaner)
+}
 
-    Ok(results)
+/// Reports what cleaning `manager` would free without deleting anything.
+pub fn preview_package_cache_cleanup(manager: &str) -> Result<CleanupResult, String> {
+    let registry = registry();
+    let cleaner = registry
+        .get(manager)
+        .ok_or_else(|| format!("Unknown package manager: {manager}"))?;
+    Ok(cleaner.dry_run())
 }
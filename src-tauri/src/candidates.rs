@@ -0,0 +1,416 @@
+//! Cleanup candidates that ground AI recommendations in real scan data.
+//!
+//! The model is shown a list of candidates, each with a short ID, and must
+//! build its recommendations from those IDs. [`ground`] then replaces each
+//! item's path and size with the candidate's and throws out recommendations
+//! that reference anything the model was not given.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ai_client::CleanupRecommendation;
+use crate::artifacts;
+use crate::browsers;
+use crate::caches;
+use crate::cleaners::expand_home;
+use crate::database::Database;
+use crate::docker;
+use crate::docker_usage::UsageCategory;
+use crate::filesystem::{self, FileInfo};
+use crate::system::{self, dir_size_and_count};
+
+/// Setting holding the directories scanned for files, a JSON array of paths.
+pub const SCAN_DIRS_SETTING: &str = "scan_directories";
+/// Used until the user saves scan directories; matches the Settings page.
+const DEFAULT_SCAN_DIRS: &[&str] = &["~/Documents", "~/Downloads", "~/Desktop"];
+
+/// Files smaller than this are not listed as large files.
+const LARGE_FILE_MIN: u64 = 50 * 1024 * 1024;
+const LARGE_FILE_LIMIT: usize = 20;
+const DUPLICATE_GROUP_LIMIT: usize = 20;
+const ARTIFACT_LIMIT: usize = 20;
+/// Projects untouched for this many days have stale artifacts.
+const STALE_ARTIFACT_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateKind {
+    LargeFile,
+    Duplicate,
+    Docker,
+    BrowserCache,
+    PackageCache,
+    ProjectArtifact,
+    OldKernel,
+}
+
+impl CandidateKind {
+    /// Recommendation category for items of this kind.
+    pub fn category(self) -> &'static str {
+        match self {
+            CandidateKind::LargeFile => "large_file",
+            CandidateKind::Duplicate => "duplicate",
+            CandidateKind::Docker => "docker",
+            CandidateKind::BrowserCache => "browser_cache",
+            CandidateKind::PackageCache => "package_cache",
+            CandidateKind::ProjectArtifact => "project_artifact",
+            CandidateKind::OldKernel => "old_kernel",
+        }
+    }
+
+    /// The kind a grounded recommendation category stands for.
+    pub fn from_category(category: &str) -> Option<Self> {
+        [
+            CandidateKind::LargeFile,
+            CandidateKind::Duplicate,
+            CandidateKind::Docker,
+            CandidateKind::BrowserCache,
+            CandidateKind::PackageCache,
+            CandidateKind::ProjectArtifact,
+            CandidateKind::OldKernel,
+        ]
+        .into_iter()
+        .find(|k| k.category() == category)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Candidate {
+    pub id: String,
+    pub kind: CandidateKind,
+    /// Filesystem path, or `docker:<category>` / `kernel:<package>`.
+    pub path: String,
+    pub size: u64,
+    pub description: String,
+}
+
+/// The user's scan directories, with `~` expanded.
+pub fn scan_dirs(db: &Database) -> Vec<PathBuf> {
+    let saved: Option<Vec<String>> = db
+        .get_setting(SCAN_DIRS_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok());
+    match saved {
+        Some(dirs) if !dirs.is_empty() => dirs.iter().map(|d| expand_home(d)).collect(),
+        _ => DEFAULT_SCAN_DIRS.iter().map(|d| expand_home(d)).collect(),
+    }
+}
+
+/// Collects candidates from every source. Files, duplicates and project
+/// artifacts are looked for in `scan_dirs`; sources that fail are skipped.
+pub fn collect_candidates(scan_dirs: &[PathBuf]) -> Vec<Candidate> {
+    let files: Vec<FileInfo> = scan_dirs
+        .iter()
+        .filter_map(|dir| filesystem::scan_directory(&dir.to_string_lossy()).ok())
+        .flatten()
+        .collect();
+
+    let mut candidates = Vec::new();
+    candidates.extend(large_files(&files));
+    candidates.extend(duplicates(&files));
+    candidates.extend(docker_candidates());
+    candidates.extend(browser_caches());
+    candidates.extend(package_caches());
+    candidates.extend(stale_artifacts(scan_dirs));
+    candidates.extend(old_kernels());
+    candidates
+}
+
+fn candidate(
+    kind: CandidateKind,
+    index: usize,
+    path: &str,
+    size: u64,
+    description: String,
+) -> Candidate {
+    let prefix = match kind {
+        CandidateKind::LargeFile => "large",
+        CandidateKind::Duplicate => "dup",
+        CandidateKind::Docker => "docker",
+        CandidateKind::BrowserCache => "browser",
+        CandidateKind::PackageCache => "pkg",
+        CandidateKind::ProjectArtifact => "artifact",
+        CandidateKind::OldKernel => "kernel",
+    };
+    Candidate {
+        id: format!("{prefix}-{}", index + 1),
+        kind,
+        path: path.to_string(),
+        size,
+        description,
+    }
+}
+
+fn large_files(files: &[FileInfo]) -> Vec<Candidate> {
+    let mut large: Vec<&FileInfo> = files
+        .iter()
+        .filter(|f| !f.is_directory && f.size as u64 >= LARGE_FILE_MIN)
+        .collect();
+    large.sort_by_key(|f| std::cmp::Reverse(f.size));
+    large
+        .into_iter()
+        .take(LARGE_FILE_LIMIT)
+        .enumerate()
+        .map(|(i, f)| {
+            let modified = chrono::DateTime::from_timestamp(f.modified_at, 0)
+                .map(|t| t.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            candidate(
+                CandidateKind::LargeFile,
+                i,
+                &f.path,
+                f.size as u64,
+                format!("Large file, last modified {modified}"),
+            )
+        })
+        .collect()
+}
+
+/// One candidate per redundant copy; the oldest file of each group is the
+/// one kept and is never a candidate.
+fn duplicates(files: &[FileInfo]) -> Vec<Candidate> {
+    let Ok(mut groups) = filesystem::find_duplicates(files) else {
+        return Vec::new();
+    };
+    groups
+        .sort_by_key(|g| std::cmp::Reverse(g.first().map_or(0, |f| f.size) * (g.len() as i64 - 1)));
+    let mut candidates = Vec::new();
+    for mut group in groups.into_iter().take(DUPLICATE_GROUP_LIMIT) {
+        group.sort_by_key(|f| f.modified_at);
+        let Some((kept, copies)) = group.split_first() else {
+            continue;
+        };
+        for copy in copies {
+            candidates.push(candidate(
+                CandidateKind::Duplicate,
+                candidates.len(),
+                &copy.path,
+                copy.size as u64,
+                format!("Duplicate of {}", kept.path),
+            ));
+        }
+    }
+    candidates
+}
+
+fn docker_candidates() -> Vec<Candidate> {
+    let Ok(info) = docker::get_docker_info(None) else {
+        return Vec::new();
+    };
+    info.disk_usage
+        .iter()
+        // Volumes hold app data and are never pruned from a recommendation.
+        .filter(|c| c.reclaimable > 0 && c.category != UsageCategory::Volumes)
+        .enumerate()
+        .map(|(i, c)| {
+            let target = match c.category {
+                UsageCategory::Images => "images",
+                UsageCategory::Containers => "containers",
+                UsageCategory::Volumes => "volumes",
+                UsageCategory::BuildCache => "build-cache",
+            };
+            candidate(
+                CandidateKind::Docker,
+                i,
+                &format!("docker:{target}"),
+                c.reclaimable,
+                format!(
+                    "Reclaimable {} {target} ({} of {} in use)",
+                    info.runtime, c.active_count, c.total_count
+                ),
+            )
+        })
+        .collect()
+}
+
+fn browser_caches() -> Vec<Candidate> {
+    browsers::get_browser_caches()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| c.size > 0)
+        .enumerate()
+        .map(|(i, c)| {
+            candidate(
+                CandidateKind::BrowserCache,
+                i,
+                &c.path,
+                c.size,
+                format!("{} disk cache", c.manager),
+            )
+        })
+        .collect()
+}
+
+fn package_caches() -> Vec<Candidate> {
+    caches::get_package_caches()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| c.exists && c.size > 0)
+        .enumerate()
+        .map(|(i, c)| {
+            candidate(
+                CandidateKind::PackageCache,
+                i,
+                &c.path,
+                c.size,
+                format!("{} package cache", c.manager),
+            )
+        })
+        .collect()
+}
+
+fn stale_artifacts(scan_dirs: &[PathBuf]) -> Vec<Candidate> {
+    let cutoff = chrono::Utc::now().timestamp() - STALE_ARTIFACT_DAYS * 86_400;
+    let mut stale: Vec<artifacts::ProjectArtifact> = scan_dirs
+        .iter()
+        .filter_map(|dir| artifacts::find_project_artifacts(&dir.to_string_lossy(), None).ok())
+        .flatten()
+        .filter(|a| a.last_touched.is_some_and(|t| t < cutoff))
+        .collect();
+    stale.sort_by_key(|a| std::cmp::Reverse(a.size));
+    stale
+        .into_iter()
+        .take(ARTIFACT_LIMIT)
+        .enumerate()
+        .map(|(i, a)| {
+            let days =
+                (chrono::Utc::now().timestamp() - a.last_touched.unwrap_or_default()) / 86_400;
+            candidate(
+                CandidateKind::ProjectArtifact,
+                i,
+                &a.path,
+                a.size,
+                format!("{} of {}, untouched for {days} days", a.kind, a.project),
+            )
+        })
+        .collect()
+}
+
+fn old_kernels() -> Vec<Candidate> {
+    old_kernel_packages()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (package, version))| {
+            candidate(
+                CandidateKind::OldKernel,
+                i,
+                &format!("kernel:{package}"),
+                dir_size_and_count(&kernel_modules_dir(&version)).0,
+                format!("Kernel {version}, not running"),
+            )
+        })
+        .collect()
+}
+
+/// Installed kernel packages other than the running one and the newest one,
+/// with their versions. Metapackages such as `linux-image-generic` carry no
+/// version and are skipped.
+pub fn old_kernel_packages() -> Vec<(String, String)> {
+    let running = std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .map(|r| r.trim().to_string())
+        .unwrap_or_default();
+    let mut kernels: Vec<(String, String)> = system::get_old_kernels()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|package| {
+            let version = package
+                .trim_start_matches("kernel-core-")
+                .trim_start_matches("linux-image-")
+                .to_string();
+            (package, version)
+        })
+        .filter(|(_, version)| version.starts_with(|c: char| c.is_ascii_digit()))
+        .collect();
+    kernels.sort_by_cached_key(|(_, version)| version_key(version));
+    kernels.pop();
+    kernels.retain(|(_, version)| *version != running);
+    kernels
+}
+
+pub fn kernel_modules_dir(version: &str) -> PathBuf {
+    Path::new("/lib/modules").join(version)
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum VersionPart {
+    Number(u64),
+    Text(String),
+}
+
+/// Splits a version into digit and non-digit runs so that numbers compare
+/// numerically: `5.15.0-100` sorts after `5.15.0-99`.
+fn version_key(version: &str) -> Vec<VersionPart> {
+    let mut parts = Vec::new();
+    let mut rest = version;
+    while let Some(first) = rest.chars().next() {
+        let digits = first.is_ascii_digit();
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != digits)
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(end);
+        parts.push(match run.parse() {
+            Ok(n) if digits => VersionPart::Number(n),
+            _ => VersionPart::Text(run.to_string()),
+        });
+        rest = tail;
+    }
+    parts
+}
+
+/// Keeps the recommendations whose items all reference candidates, filling
+/// each item's path and size from its candidate. A recommendation is thrown
+/// out if an item names an unknown candidate, gives a path other than the
+/// candidate's, or mixes candidate kinds.
+pub fn ground(
+    recommendations: Vec<CleanupRecommendation>,
+    candidates: &[Candidate],
+) -> Vec<CleanupRecommendation> {
+    let by_id: HashMap<&str, &Candidate> = candidates.iter().map(|c| (c.id.as_str(), c)).collect();
+    recommendations
+        .into_iter()
+        .filter_map(|rec| match ground_one(rec, &by_id) {
+            Ok(rec) => Some(rec),
+            Err(e) => {
+                log::warn!("Dropping AI recommendation: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+fn ground_one(
+    mut rec: CleanupRecommendation,
+    by_id: &HashMap<&str, &Candidate>,
+) -> Result<CleanupRecommendation, String> {
+    let mut kind = None;
+    for item in &mut rec.items {
+        let candidate = by_id.get(item.candidate_id.as_str()).ok_or_else(|| {
+            format!(
+                "{} references unknown candidate {:?}",
+                rec.id, item.candidate_id
+            )
+        })?;
+        if !item.path.is_empty() && item.path != candidate.path {
+            return Err(format!(
+                "{} gives path {} for candidate {}",
+                rec.id, item.path, candidate.id
+            ));
+        }
+        if kind.is_some_and(|k| k != candidate.kind) {
+            return Err(format!("{} mixes candidate kinds", rec.id));
+        }
+        kind = Some(candidate.kind);
+        item.path = candidate.path.clone();
+        item.size = candidate.size;
+        if item.description.is_empty() {
+            item.description = candidate.description.clone();
+        }
+    }
+    let kind = kind.ok_or_else(|| format!("{} has no items", rec.id))?;
+    rec.category = kind.category().to_string();
+    rec.space_reclaimable = rec.items.iter().map(|i| i.size).sum();
+    Ok(rec)
+}
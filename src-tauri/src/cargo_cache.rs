@@ -0,0 +1,301 @@
+//! Cargo-aware cleanup, similar to `cargo-cache` and `cargo-sweep`.
+//!
+//! Covers the parts of `$CARGO_HOME` that Cargo can re-create (downloaded
+//! `.crate` archives, their extracted sources and git checkouts) and the
+//! per-project `target/` directories where most of the space goes.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::artifacts::artifact_dirs;
+use crate::cleaners::{measure_cleanup, remove_dir_contents, CacheCleaner};
+use crate::filesystem::modified_timestamp;
+use crate::path_safety::{self, PathPolicy};
+use crate::system::{dir_size_and_count, CleanupResult};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CargoCacheSummary {
+    pub cargo_home: String,
+    pub registry_index: u64,
+    pub registry_cache: u64,
+    pub registry_src: u64,
+    pub git_db: u64,
+    pub git_checkouts: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CargoTargetDir {
+    pub path: String,
+    pub project: String,
+    pub size: u64,
+    /// Unix timestamp of the most recent build output.
+    pub last_build: i64,
+}
+
+pub fn cargo_home() -> PathBuf {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".cargo"))
+}
+
+/// Registry cleaner for the package cache listing.
+///
+/// Removes downloaded archives, extracted sources and git checkouts. The
+/// registry index and git databases are kept so Cargo can rebuild the rest
+/// without a full re-fetch.
+pub struct CargoCleaner;
+
+impl CacheCleaner for CargoCleaner {
+    fn name(&self) -> &str {
+        "cargo"
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        let home = cargo_home();
+        vec![
+            home.join("registry/cache"),
+            home.join("registry/src"),
+            home.join("git/checkouts"),
+        ]
+    }
+
+    fn clean(&self) -> Result<CleanupResult, String> {
+        for path in self.paths().iter().filter(|p| p.exists()) {
+            remove_dir_contents(path, &[cargo_home()])
+                .map_err(|e| format!("cargo cache clean: {e}"))?;
+        }
+        Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: "Cargo registry sources and git checkouts cleaned".to_string(),
+        })
+    }
+}
+
+pub fn get_cargo_cache_summary() -> Result<CargoCacheSummary, String> {
+    let home = cargo_home();
+    let size = |p: &str| {
+        let path = home.join(p);
+        if path.exists() {
+            dir_size_and_count(&path).0
+        } else {
+            0
+        }
+    };
+    Ok(CargoCacheSummary {
+        cargo_home: home.to_string_lossy().to_string(),
+        registry_index: size("registry/index"),
+        registry_cache: size("registry/cache"),
+        registry_src: size("registry/src"),
+        git_db: size("git/db"),
+        git_checkouts: size("git/checkouts"),
+    })
+}
+
+/// Finds Cargo `target/` directories under `roots`, largest first.
+pub fn find_target_dirs(roots: &[String]) -> Result<Vec<CargoTargetDir>, String> {
+    let mut found = Vec::new();
+    for root in roots {
+        let root = Path::new(root);
+        if !root.is_dir() {
+            return Err(format!("Not a directory: {}", root.display()));
+        }
+        found.extend(
+            artifact_dirs(root)
+                .into_iter()
+                .map(|(path, _)| path)
+                .filter(|path| is_cargo_target_dir(path)),
+        );
+    }
+
+    let mut dirs: Vec<CargoTargetDir> = found
+        .par_iter()
+        .map(|path| CargoTargetDir {
+            path: path.to_string_lossy().to_string(),
+            project: path
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size: dir_size_and_count(path).0,
+            last_build: last_build_time(path),
+        })
+        .collect();
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.size));
+    Ok(dirs)
+}
+
+/// Removes the given `target/` directories after re-checking each one.
+pub fn clean_target_dirs(paths: &[String]) -> Result<CleanupResult, String> {
+    let targets: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    if let Some(bad) = targets.iter().find(|p| !is_cargo_target_dir(p)) {
+        return Err(format!("Not a Cargo target directory: {}", bad.display()));
+    }
+    measure_cleanup(&targets, || {
+        remove_each(&targets, &path_safety::user_roots())
+    })
+}
+
+/// Removes all but the `keep` newest versions of every crate in the registry
+/// archive and source caches.
+pub fn prune_registry(keep: usize) -> Result<CleanupResult, String> {
+    let home = cargo_home();
+    let mut stale = Vec::new();
+    for dir in ["registry/cache", "registry/src"] {
+        let Ok(registries) = fs::read_dir(home.join(dir)) else {
+            continue;
+        };
+        for registry in registries.flatten() {
+            stale.extend(stale_versions(&registry.path(), keep));
+        }
+    }
+    if stale.is_empty() {
+        return Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: "No stale crate versions".to_string(),
+        });
+    }
+    measure_cleanup(&stale, || remove_each(&stale, &[cargo_home()]))
+}
+
+/// Removes git checkouts; they are re-created from `git/db` on the next build.
+pub fn clean_git_checkouts() -> Result<CleanupResult, String> {
+    let checkouts = cargo_home().join("git/checkouts");
+    measure_cleanup(std::slice::from_ref(&checkouts), || {
+        if checkouts.exists() {
+            remove_dir_contents(&checkouts, &[cargo_home()])
+                .map_err(|e| format!("cargo git checkouts: {e}"))?;
+        }
+        Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: "Cargo git checkouts cleaned".to_string(),
+        })
+    })
+}
+
+/// Removes `paths`, each of which must lie inside one of `roots`.
+fn remove_each(paths: &[PathBuf], roots: &[PathBuf]) -> Result<CleanupResult, String> {
+    let policy = PathPolicy::new(roots);
+    let mut removed = 0;
+    let mut messages = Vec::new();
+    for path in paths.iter().filter(|p| p.exists()) {
+        match policy.remove(path) {
+            Ok(_) => removed += 1,
+            Err(e) => messages.push(e),
+        }
+    }
+    messages.insert(0, format!("Removed {removed} of {} items", paths.len()));
+    Ok(CleanupResult {
+        success: removed > 0,
+        space_freed: 0,
+        message: messages.join("; "),
+    })
+}
+
+/// A directory named `target` next to a `Cargo.toml` that Cargo has written
+/// its `CACHEDIR.TAG` or `.rustc_info.json` marker into.
+pub fn is_cargo_target_dir(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == "target")
+        && path.is_dir()
+        && path
+            .parent()
+            .is_some_and(|p| p.join("Cargo.toml").is_file())
+        && (path.join("CACHEDIR.TAG").exists() || path.join(".rustc_info.json").exists())
+}
+
+/// Newest mtime among the profile directories and their direct children.
+fn last_build_time(target: &Path) -> i64 {
+    WalkDir::new(target)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| modified_timestamp(&m))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Entries in one registry directory that fall outside the `keep` newest
+/// versions of their crate. Entries are `name-version.crate` archives or
+/// `name-version` source directories.
+fn stale_versions(registry: &Path, keep: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(registry) else {
+        return Vec::new();
+    };
+    let mut by_crate: HashMap<String, Vec<(Version, PathBuf)>> = HashMap::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let stem = file_name.strip_suffix(".crate").unwrap_or(&file_name);
+        if let Some((name, version)) = split_crate_version(stem) {
+            by_crate
+                .entry(name.to_string())
+                .or_default()
+                .push((version, entry.path()));
+        }
+    }
+    by_crate
+        .into_values()
+        .flat_map(|mut versions| {
+            versions.sort_by(|a, b| b.0.cmp(&a.0));
+            versions.into_iter().skip(keep).map(|(_, path)| path)
+        })
+        .collect()
+}
+
+/// Splits `serde_json-1.0.114` into the crate name and its version. Crate
+/// names may themselves contain `-<digit>` (e.g. `md-5`), so the version is
+/// the first suffix that parses as `major.minor.patch`.
+fn split_crate_version(stem: &str) -> Option<(&str, Version)> {
+    stem.match_indices('-').find_map(|(i, _)| {
+        let version = Version::parse(&stem[i + 1..])?;
+        Some((&stem[..i], version))
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    core: (u64, u64, u64),
+    pre: Option<String>,
+}
+
+impl Version {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.split('+').next()?;
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (s, None),
+        };
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        let version = (parts.next()??, parts.next()??, parts.next()??);
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Version { core: version, pre })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core
+            .cmp(&other.core)
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,314 @@
+//! Rule-based file classifier that works without an AI provider.
+//!
+//! Produces the same [`AIAnalysis`] fields as the model from the file's
+//! location (cache, temp and dependency directories), its extension or, when
+//! that says nothing, its sniffed content type, and its age and size. Files
+//! the rules are sure about are not sent to the model; only the ambiguous
+//! ones are, and all of them are when no provider is configured.
+//!
+//! A directory named `cache` or `temp` can be anything in a user's tree, so
+//! the cache and temp rules only hold under `~/.cache`, `/tmp` and
+//! `/var/tmp`, inside directories only tools create (`__pycache__`, `.tox`)
+//! and inside cache directories tagged with a `CACHEDIR.TAG` file.
+
+use std::path::{Path, PathBuf};
+
+use crate::ai_client::AIAnalysis;
+use crate::filesystem::FileInfo;
+
+/// Directories only the program that owns them creates, and whose contents
+/// it regenerates.
+const TOOL_CACHE_DIRS: &[&str] = &[
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    ".gradle",
+    ".npm",
+    ".tox",
+];
+/// Common cache directory names; they only count inside a cache root or
+/// when tagged with [`CACHEDIR_TAG`].
+const CACHE_DIRS: &[&str] = &["cache", ".cache", "caches", "thumbnails"];
+/// Marker file of the Cache Directory Tagging specification.
+const CACHEDIR_TAG: &str = "CACHEDIR.TAG";
+const TEMP_ROOTS: &[&str] = &["/tmp", "/var/tmp"];
+/// Dependencies that a package manager re-installs from a lock file.
+const DEPENDENCY_DIRS: &[&str] = &["node_modules", ".venv", "venv", "bower_components"];
+/// Version control internals and credentials, never to be cleaned file by file.
+const KEEP_DIRS: &[&str] = &[".git", ".hg", ".svn", ".ssh", ".gnupg", ".password-store"];
+
+const TEMP_EXTS: &[&str] = &[
+    "tmp",
+    "temp",
+    "bak",
+    "old",
+    "swp",
+    "swo",
+    "part",
+    "partial",
+    "crdownload",
+];
+const LOG_EXTS: &[&str] = &["log"];
+const BUILD_EXTS: &[&str] = &["o", "pyc", "pyo", "class", "rlib", "rmeta"];
+const DOCUMENT_EXTS: &[&str] = &[
+    "pdf", "doc", "docx", "odt", "rtf", "txt", "md", "tex", "epub", "xls", "xlsx", "ods", "csv",
+    "ppt", "pptx", "odp",
+];
+const CODE_EXTS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "c", "h", "cc", "cpp", "hpp", "go", "java", "kt", "rb",
+    "php", "cs", "swift", "sh", "bash", "zsh", "lua", "sql", "html", "css", "scss", "vue",
+];
+const MEDIA_EXTS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "heic", "raw", "cr2", "nef", "svg", "mp4", "mkv", "mov",
+    "avi", "webm", "mp3", "flac", "ogg", "wav", "m4a", "opus",
+];
+const CONFIG_EXTS: &[&str] = &[
+    "conf", "cfg", "ini", "toml", "yaml", "yml", "json", "xml", "env",
+];
+/// Archives, disk images and installers: often leftovers once extracted or
+/// installed, but only the user knows.
+const ARCHIVE_EXTS: &[&str] = &[
+    "zip", "tar", "gz", "tgz", "bz2", "xz", "zst", "7z", "rar", "iso", "img", "deb", "rpm",
+    "appimage", "dmg", "exe", "msi", "flatpak", "snap",
+];
+
+/// Temp files younger than this may still be in use.
+const TEMP_MIN_AGE_DAYS: i64 = 1;
+/// Logs older than this are safe to delete.
+const LOG_MAX_AGE_DAYS: i64 = 30;
+/// Documents and media this old and this large are worth a second look.
+const STALE_AGE_DAYS: i64 = 365;
+const LARGE_FILE_BYTES: u64 = 500 * 1024 * 1024;
+/// Archives older than this are likely leftovers.
+const ARCHIVE_STALE_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Temp,
+    Log,
+    Build,
+    Document,
+    Code,
+    Media,
+    Config,
+    Archive,
+    Unknown,
+}
+
+pub struct Classification {
+    pub analysis: AIAnalysis,
+    /// Whether the rules are sure enough that asking the model adds nothing.
+    pub confident: bool,
+}
+
+pub fn classify(file: &FileInfo) -> Classification {
+    let path = Path::new(&file.path);
+    let age_days = (chrono::Utc::now().timestamp() - file.modified_at) / 86_400;
+    let size = file.size.max(0) as u64;
+    // A directory is judged by its own name too, so `node_modules` itself
+    // classifies like its contents.
+    let location = if file.is_directory {
+        Some(path)
+    } else {
+        path.parent()
+    };
+    let dirs: Vec<String> = location
+        .into_iter()
+        .flat_map(|p| p.components())
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    let in_dir = |names: &[&str]| dirs.iter().any(|d| names.contains(&d.as_str()));
+    let under = |root: &Path| path.starts_with(root) && path != root;
+    let verdict = Verdict { file };
+
+    if in_dir(KEEP_DIRS) {
+        return verdict.keep("config", 0.95, "Part of version control or credentials");
+    }
+    if in_dir(DEPENDENCY_DIRS) {
+        return verdict.delete(
+            "cache",
+            0.15,
+            "Installed dependency; the package manager re-installs it",
+        );
+    }
+
+    let kind = if file.name.ends_with('~') {
+        Kind::Temp
+    } else {
+        match file.extension.as_deref().map(str::to_lowercase) {
+            Some(ext) => kind_of_extension(&ext),
+            None => Kind::Unknown,
+        }
+    };
+    let kind = match kind {
+        Kind::Unknown if !file.is_directory => sniff(path),
+        kind => kind,
+    };
+    // Work the user made may end up in a cache or temp directory too.
+    let personal = match kind {
+        Kind::Code => Some("code"),
+        Kind::Document => Some("document"),
+        Kind::Media => Some("media"),
+        _ => None,
+    };
+
+    let in_cache = cache_roots().iter().any(|r| under(r))
+        || in_dir(TOOL_CACHE_DIRS)
+        || (in_dir(CACHE_DIRS) && in_tagged_cache(location));
+    let in_temp = TEMP_ROOTS.iter().any(|r| under(Path::new(r)));
+    if let Some(category) = personal.filter(|_| in_cache || in_temp) {
+        return verdict.review(
+            category,
+            0.5,
+            "Inside a cache or temp directory, but looks like a document, source or media file",
+            false,
+        );
+    }
+    if in_cache {
+        return verdict.delete("cache", 0.1, "Inside a cache directory");
+    }
+    let kind = if in_temp { Kind::Temp } else { kind };
+
+    let stale = age_days >= STALE_AGE_DAYS && size >= LARGE_FILE_BYTES;
+    match kind {
+        Kind::Temp if age_days >= TEMP_MIN_AGE_DAYS => {
+            verdict.delete("temp", 0.1, "Temporary or backup file")
+        }
+        Kind::Temp => verdict.review(
+            "temp",
+            0.3,
+            "Recent temporary file that may still be in use",
+            true,
+        ),
+        Kind::Log if age_days >= LOG_MAX_AGE_DAYS => verdict.delete(
+            "log",
+            0.2,
+            &format!("Log file not written for {age_days} days"),
+        ),
+        Kind::Log => verdict.review("log", 0.4, "Recent log file", true),
+        Kind::Build => verdict.delete("cache", 0.15, "Compiler output; rebuilt when needed"),
+        Kind::Document if stale => verdict.review(
+            "document",
+            0.5,
+            &format!("Large document not modified for {age_days} days"),
+            false,
+        ),
+        Kind::Document => verdict.keep("document", 0.8, "Document"),
+        Kind::Code => verdict.keep("code", 0.85, "Source code"),
+        Kind::Media if stale => verdict.review(
+            "media",
+            0.5,
+            &format!("Large media file not modified for {age_days} days"),
+            false,
+        ),
+        Kind::Media => verdict.keep("media", 0.7, "Photo, video or audio file"),
+        Kind::Config => verdict.keep("config", 0.9, "Configuration file"),
+        Kind::Archive if age_days >= ARCHIVE_STALE_DAYS => verdict.review(
+            "other",
+            0.3,
+            &format!("Archive or installer not modified for {age_days} days"),
+            false,
+        ),
+        Kind::Archive => verdict.review("other", 0.5, "Archive or installer", false),
+        Kind::Unknown => verdict.review("other", 0.5, "No rule matched this file", false),
+    }
+}
+
+fn cache_roots() -> Vec<PathBuf> {
+    dirs::home_dir()
+        .map(|h| h.join(".cache"))
+        .into_iter()
+        .chain(dirs::cache_dir())
+        .collect()
+}
+
+/// Whether an ancestor of `location` with a common cache name carries a
+/// `CACHEDIR.TAG` file.
+fn in_tagged_cache(location: Option<&Path>) -> bool {
+    location.into_iter().flat_map(Path::ancestors).any(|dir| {
+        dir.file_name()
+            .is_some_and(|n| CACHE_DIRS.contains(&n.to_string_lossy().to_lowercase().as_str()))
+            && dir.join(CACHEDIR_TAG).is_file()
+    })
+}
+
+fn kind_of_extension(ext: &str) -> Kind {
+    let tables: [(&[&str], Kind); 8] = [
+        (TEMP_EXTS, Kind::Temp),
+        (LOG_EXTS, Kind::Log),
+        (BUILD_EXTS, Kind::Build),
+        (DOCUMENT_EXTS, Kind::Document),
+        (CODE_EXTS, Kind::Code),
+        (MEDIA_EXTS, Kind::Media),
+        (CONFIG_EXTS, Kind::Config),
+        (ARCHIVE_EXTS, Kind::Archive),
+    ];
+    tables
+        .iter()
+        .find(|(exts, _)| exts.contains(&ext))
+        .map_or(Kind::Unknown, |(_, kind)| *kind)
+}
+
+/// Kind from the file's magic bytes, for files without a known extension.
+fn sniff(path: &Path) -> Kind {
+    let Ok(Some(found)) = infer::get_from_path(path) else {
+        return Kind::Unknown;
+    };
+    match found.matcher_type() {
+        infer::MatcherType::Image | infer::MatcherType::Video | infer::MatcherType::Audio => {
+            Kind::Media
+        }
+        infer::MatcherType::Doc | infer::MatcherType::Book | infer::MatcherType::Text => {
+            Kind::Document
+        }
+        infer::MatcherType::Archive | infer::MatcherType::App => Kind::Archive,
+        _ => Kind::Unknown,
+    }
+}
+
+struct Verdict<'a> {
+    file: &'a FileInfo,
+}
+
+impl Verdict<'_> {
+    fn build(
+        &self,
+        category: &str,
+        importance: f64,
+        recommendation: &str,
+        safe_to_delete: bool,
+        reason: &str,
+        confident: bool,
+    ) -> Classification {
+        Classification {
+            analysis: AIAnalysis {
+                file_path: self.file.path.clone(),
+                category: category.to_string(),
+                importance_score: importance,
+                recommendation: recommendation.to_string(),
+                safe_to_delete,
+                reason: reason.to_string(),
+            },
+            confident,
+        }
+    }
+
+    fn keep(&self, category: &str, importance: f64, reason: &str) -> Classification {
+        self.build(category, importance, "keep", false, reason, true)
+    }
+
+    fn delete(&self, category: &str, importance: f64, reason: &str) -> Classification {
+        self.build(category, importance, "delete", true, reason, true)
+    }
+
+    fn review(
+        &self,
+        category: &str,
+        importance: f64,
+        reason: &str,
+        confident: bool,
+    ) -> Classification {
+        self.build(category, importance, "review", false, reason, confident)
+    }
+}
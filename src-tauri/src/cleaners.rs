@@ -0,0 +1,511 @@
+//! Pluggable cache cleaner registry.
+//!
+//! Every cache CleanOS knows how to clean implements [`CacheCleaner`] and is
+//! looked up by name through a [`CleanerRegistry`]. Built-in cleaners are
+//! registered by [`crate::caches`]; additional ones can be declared in
+//! `~/.config/cleanos-ai/cleaners.toml` (or `cleaners.json`) without touching
+//! the code:
+//!
+//! ```toml
+//! [[cleaner]]
+//! name = "bazel"
+//! paths = ["~/.cache/bazel"]
+//! command = ["bazel", "clean", "--expunge"]
+//! ```
+//!
+//! A cleaner only empties directories inside its allowed roots: `~/.cache`
+//! unless a built-in cleaner names its toolchain home. User-declared cleaners
+//! are always limited to `~/.cache`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+use crate::path_safety::PathPolicy;
+use crate::system::{self, dir_size_and_count, CleanupResult, PackageCacheInfo};
+
+/// A cache that can be detected, measured and cleaned.
+pub trait CacheCleaner: Send + Sync {
+    /// Identifier shown in the UI and passed back to `clean_package_cache`.
+    fn name(&self) -> &str;
+
+    /// Directories owned by this cache.
+    fn paths(&self) -> Vec<PathBuf>;
+
+    /// Whether the cache is present on this machine.
+    fn detect(&self) -> bool {
+        self.paths().iter().any(|p| p.exists())
+    }
+
+    /// Total size in bytes of all existing cache directories.
+    fn size(&self) -> u64 {
+        self.paths()
+            .iter()
+            .filter(|p| p.exists())
+            .map(|p| dir_size_and_count(p).0)
+            .sum()
+    }
+
+    /// Whether cleaning needs elevated privileges (run through `sudo -n`).
+    fn requires_root(&self) -> bool {
+        false
+    }
+
+    /// Report what [`CacheCleaner::clean`] would free without changing anything.
+    fn dry_run(&self) -> CleanupResult {
+        let existing: Vec<String> = self
+            .paths()
+            .iter()
+            .filter(|p| p.exists())
+            .map(|p| p.display().to_string())
+            .collect();
+        CleanupResult {
+            success: true,
+            space_freed: self.size(),
+            message: if existing.is_empty() {
+                format!("Nothing to clean for {}", self.name())
+            } else if self.requires_root() {
+                format!(
+                    "Would clean {} (requires elevated privileges)",
+                    existing.join(", ")
+                )
+            } else {
+                format!("Would clean {}", existing.join(", "))
+            },
+        }
+    }
+
+    /// Remove the cache contents.
+    fn clean(&self) -> Result<CleanupResult, String>;
+
+    /// Summary used by the `get_package_caches` listing.
+    fn info(&self) -> PackageCacheInfo {
+        let paths = self.paths();
+        let path = paths
+            .iter()
+            .find(|p| p.exists())
+            .or_else(|| paths.first())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let exists = self.detect();
+        PackageCacheInfo {
+            manager: self.name().to_string(),
+            path,
+            size: if exists { self.size() } else { 0 },
+            exists,
+        }
+    }
+}
+
+/// Declarative cleaner definition, as read from the user's cleaner file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CleanerSpec {
+    pub name: String,
+    pub paths: Vec<String>,
+    /// Native clean command. When absent the directory contents are removed.
+    #[serde(default)]
+    pub command: Option<Vec<String>>,
+    /// Empty the directories directly when `command` is not installed.
+    #[serde(default)]
+    pub dir_fallback: bool,
+    #[serde(default)]
+    pub requires_root: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CleanerFile {
+    #[serde(default)]
+    cleaner: Vec<CleanerSpec>,
+}
+
+/// Cleaner that either runs a native command or empties its directories.
+///
+/// Used for both the built-in package managers and user-declared cleaners.
+/// A cleaner without paths is detected by its command being installed.
+pub struct SpecCleaner {
+    name: String,
+    paths: Vec<PathBuf>,
+    /// Directories `paths` must lie inside to be emptied.
+    roots: Vec<PathBuf>,
+    command: Option<Vec<String>>,
+    dir_fallback: bool,
+    requires_root: bool,
+}
+
+impl SpecCleaner {
+    /// Cleaner that empties `paths` directly.
+    pub fn dirs(name: &str, paths: Vec<PathBuf>) -> Self {
+        SpecCleaner {
+            name: name.to_string(),
+            paths,
+            roots: default_cache_roots(),
+            command: None,
+            dir_fallback: false,
+            requires_root: false,
+        }
+    }
+
+    /// Cleaner that delegates to a native CLI command.
+    pub fn command(name: &str, paths: Vec<PathBuf>, command: &[&str]) -> Self {
+        SpecCleaner {
+            name: name.to_string(),
+            paths,
+            roots: default_cache_roots(),
+            command: Some(command.iter().map(|s| s.to_string()).collect()),
+            dir_fallback: false,
+            requires_root: false,
+        }
+    }
+
+    /// Allows emptying directories inside `roots` instead of `~/.cache`.
+    pub fn within(mut self, roots: Vec<PathBuf>) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    pub fn root(mut self) -> Self {
+        self.requires_root = true;
+        self
+    }
+
+    /// Empty the directories directly when the command is not installed.
+    ///
+    /// Only for caches whose contents are plain, re-downloadable files.
+    pub fn with_dir_fallback(mut self) -> Self {
+        self.dir_fallback = true;
+        self
+    }
+
+    fn program(&self) -> Option<&str> {
+        self.command
+            .as_ref()
+            .and_then(|c| c.first())
+            .map(String::as_str)
+    }
+
+    fn empty_dirs(&self) -> Result<CleanupResult, String> {
+        for path in self.paths.iter().filter(|p| p.exists()) {
+            remove_dir_contents(path, &self.roots)
+                .map_err(|e| format!("{} cache clean: {e}", self.name))?;
+        }
+        Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: format!("{} cache cleaned", self.name),
+        })
+    }
+}
+
+impl From<CleanerSpec> for SpecCleaner {
+    fn from(spec: CleanerSpec) -> Self {
+        SpecCleaner {
+            name: spec.name,
+            paths: spec.paths.iter().map(|p| expand_home(p)).collect(),
+            roots: default_cache_roots(),
+            command: spec.command.filter(|c| !c.is_empty()),
+            dir_fallback: spec.dir_fallback,
+            requires_root: spec.requires_root,
+        }
+    }
+}
+
+impl CacheCleaner for SpecCleaner {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.paths.clone()
+    }
+
+    fn detect(&self) -> bool {
+        match self.program() {
+            Some(program) if self.paths.is_empty() => command_exists(program),
+            _ => self.paths.iter().any(|p| p.exists()),
+        }
+    }
+
+    fn requires_root(&self) -> bool {
+        self.requires_root
+    }
+
+    fn clean(&self) -> Result<CleanupResult, String> {
+        match (&self.command, self.program()) {
+            (Some(cmd), Some(program)) if command_exists(program) => {
+                run_clean_command(&self.name, cmd, self.requires_root)
+            }
+            (Some(_), Some(program)) if !self.dir_fallback || self.requires_root => {
+                Err(format!("{program} is not installed or not on PATH"))
+            }
+            _ => self.empty_dirs(),
+        }
+    }
+}
+
+/// Smallest cleanup for which a file/filesystem mismatch is worth reporting;
+/// below this, block rounding and unrelated writes dominate the free-space delta.
+const MISMATCH_REPORT_MIN: u64 = 16 * 1024 * 1024;
+
+/// Runs `clean` and reports the space it actually freed.
+///
+/// `paths` are sized before and after the run and the difference becomes
+/// `space_freed`, replacing whatever the cleaner estimated. The free space of
+/// the filesystems holding `paths` is compared as a sanity check: it stands in
+/// when the directories could not be fully read (e.g. root-owned caches), and
+/// a large mismatch is noted in the message.
+pub fn measure_cleanup<F>(paths: &[PathBuf], clean: F) -> Result<CleanupResult, String>
+where
+    F: FnOnce() -> Result<CleanupResult, String>,
+{
+    measure(paths, false, clean)
+}
+
+/// Like [`measure_cleanup`] for a registered cleaner. Cleaners running as
+/// root are measured by free space, since their directories are usually
+/// only partly readable.
+pub fn measured_clean(cleaner: &dyn CacheCleaner) -> Result<CleanupResult, String> {
+    measure(&cleaner.paths(), cleaner.requires_root(), || {
+        cleaner.clean()
+    })
+}
+
+fn measure<F>(paths: &[PathBuf], privileged: bool, clean: F) -> Result<CleanupResult, String>
+where
+    F: FnOnce() -> Result<CleanupResult, String>,
+{
+    let probes = filesystem_probes(paths);
+    let (size_before, read_before) = total_size(paths);
+    let free_before = free_space(&probes);
+
+    let mut result = clean()?;
+
+    let (size_after, read_after) = total_size(paths);
+    let free_after = free_space(&probes);
+    let dir_freed = size_before.saturating_sub(size_after);
+    let fs_freed: u64 = free_before
+        .iter()
+        .map(|(dev, before)| {
+            free_after
+                .get(dev)
+                .unwrap_or(before)
+                .saturating_sub(*before)
+        })
+        .sum();
+
+    let unreadable = size_before == 0 || !read_before || !read_after;
+    result.space_freed = if privileged || unreadable {
+        fs_freed
+    } else {
+        dir_freed
+    };
+    if !privileged && !unreadable && dir_freed >= MISMATCH_REPORT_MIN && fs_freed < dir_freed / 2 {
+        log::debug!("Cleanup freed {dir_freed} bytes of files but {fs_freed} bytes of disk");
+        result.message = format!(
+            "{} (filesystem reports {fs_freed} bytes freed; some files may be \
+             hard-linked or still open)",
+            result.message.trim_end()
+        );
+    }
+    Ok(result)
+}
+
+/// Total size of `paths`, and whether every entry below them could be read.
+fn total_size(paths: &[PathBuf]) -> (u64, bool) {
+    let mut size = 0;
+    let mut complete = true;
+    for entry in paths
+        .iter()
+        .filter(|p| p.exists())
+        .flat_map(|p| WalkDir::new(p).follow_links(false))
+    {
+        match entry.and_then(|e| e.metadata()) {
+            Ok(meta) if meta.is_file() => size += meta.len(),
+            Ok(_) => {}
+            Err(_) => complete = false,
+        }
+    }
+    (size, complete)
+}
+
+/// One existing path per filesystem (keyed by device id) to query with `df`.
+fn filesystem_probes(paths: &[PathBuf]) -> HashMap<u64, PathBuf> {
+    let mut probes = HashMap::new();
+    for path in paths {
+        let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+            continue;
+        };
+        if let Ok(meta) = fs::metadata(existing) {
+            probes
+                .entry(meta.dev())
+                .or_insert_with(|| existing.to_path_buf());
+        }
+    }
+    probes
+}
+
+fn free_space(probes: &HashMap<u64, PathBuf>) -> HashMap<u64, u64> {
+    probes
+        .iter()
+        .map(|(dev, path)| (*dev, system::read_disk_info(&path.to_string_lossy()).2))
+        .collect()
+}
+
+/// Runs a clean command, prefixing it with `sudo -n` when root is required.
+pub fn run_clean_command(
+    name: &str,
+    cmd: &[String],
+    requires_root: bool,
+) -> Result<CleanupResult, String> {
+    let mut argv: Vec<&str> = Vec::new();
+    if requires_root {
+        argv.extend(["sudo", "-n"]);
+    }
+    argv.extend(cmd.iter().map(String::as_str));
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| format!("{name}: empty clean command"))?;
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{}: {e}", cmd.join(" ")))?;
+
+    if !output.status.success() && requires_root {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("password") {
+            return Err(format!(
+                "Cleaning {name} requires elevated privileges. \
+                 Please configure passwordless sudo, or run: sudo {}",
+                cmd.join(" ")
+            ));
+        }
+    }
+
+    Ok(CleanupResult {
+        success: output.status.success(),
+        space_freed: 0,
+        message: String::from_utf8_lossy(&output.stdout).to_string(),
+    })
+}
+
+/// Whether `program` resolves to an executable, either as a path or via `$PATH`.
+pub fn command_exists(program: &str) -> bool {
+    if program.contains('/') {
+        return Path::new(program).is_file();
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// The user's cache directory, where cleaners may empty directories unless
+/// they name other roots.
+pub fn default_cache_roots() -> Vec<PathBuf> {
+    let mut roots = vec![expand_home("~/.cache")];
+    roots.extend(dirs::cache_dir().filter(|d| !roots.contains(d)));
+    roots
+}
+
+/// Removes everything inside `dir` while keeping the directory itself.
+/// `dir` must lie inside one of `roots`, the locations the calling cleaner
+/// owns, and every entry goes through the path safety policy.
+pub fn remove_dir_contents(dir: &Path, roots: &[PathBuf]) -> Result<(), String> {
+    let policy = PathPolicy::new(roots);
+    let dir = policy.check_contents(dir)?;
+    let entries = fs::read_dir(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("{}: {e}", dir.display()))?;
+        policy.remove(&entry.path())?;
+    }
+    Ok(())
+}
+
+/// Expands a leading `~` or `$HOME` to the user's home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_default();
+    if path == "~" || path == "$HOME" {
+        home
+    } else if let Some(rest) = path
+        .strip_prefix("~/")
+        .or_else(|| path.strip_prefix("$HOME/"))
+    {
+        home.join(rest)
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// Ordered collection of cleaners, addressable by name.
+#[derive(Default)]
+pub struct CleanerRegistry {
+    cleaners: Vec<Box<dyn CacheCleaner>>,
+}
+
+impl CleanerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cleaner, replacing any existing cleaner with the same name.
+    pub fn register(&mut self, cleaner: Box<dyn CacheCleaner>) {
+        match self
+            .cleaners
+            .iter()
+            .position(|c| c.name() == cleaner.name())
+        {
+            Some(i) => self.cleaners[i] = cleaner,
+            None => self.cleaners.push(cleaner),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn CacheCleaner> {
+        self.cleaners
+            .iter()
+            .find(|c| c.name() == name)
+            .map(|c| c.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn CacheCleaner> {
+        self.cleaners.iter().map(|c| c.as_ref())
+    }
+
+    /// Registers the cleaners declared in the user's config directory.
+    ///
+    /// A malformed file is logged and skipped so that the built-in cleaners
+    /// keep working.
+    pub fn load_user_definitions(&mut self) {
+        let Some(config_dir) = dirs::config_dir() else {
+            return;
+        };
+        let dir = config_dir.join("cleanos-ai");
+        for file in ["cleaners.toml", "cleaners.json"] {
+            let path = dir.join(file);
+            if !path.exists() {
+                continue;
+            }
+            match read_cleaner_file(&path) {
+                Ok(specs) => {
+                    for spec in specs {
+                        self.register(Box::new(SpecCleaner::from(spec)));
+                    }
+                }
+                Err(e) => log::warn!("Ignoring {}: {e}", path.display()),
+            }
+        }
+    }
+}
+
+fn read_cleaner_file(path: &Path) -> Result<Vec<CleanerSpec>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Read error: {e}"))?;
+    let file: CleanerFile = if path.extension().is_some_and(|e| e == "json") {
+        serde_json::from_str(&content).map_err(|e| format!("Parse error: {e}"))?
+    } else {
+        toml::from_str(&content).map_err(|e| format!("Parse error: {e}"))?
+    };
+    Ok(file.cleaner)
+}
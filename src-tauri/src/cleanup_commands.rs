@@ -1,7 +1,24 @@
-//! Tauri IPC commands for log and browser cache cleanup operations.
+//! Tauri IPC commands for log, temp file, Trash, browser cache, `~/.cache`,
+//! Flatpak/Snap, orphan, Cargo and project artifact cleanup operations.
 
-use crate::caches;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::Emitter;
+
+use crate::artifacts::{self, ProjectArtifact};
+use crate::browsers::{
+    self, BrowserInstall, BrowserProfile, BrowserProfileInfo, CategorySize, DataCategory,
+    RunningPolicy,
+};
+use crate::cache_audit::{self, CacheEntry};
+use crate::cargo_cache::{self, CargoCacheSummary, CargoTargetDir};
+use crate::cleaners;
+use crate::orphans::{self, OrphanReport};
+use crate::sandboxed_apps::{self, SandboxedAppsReport};
 use crate::system::{self, CleanupResult, PackageCacheInfo};
+use crate::temp_files::{self, TempLocation};
+use crate::trash::{self, TrashDir};
 
 #[tauri::command]
 pub async fn get_log_info() -> Result<system::StorageCategory, String> {
@@ -13,132 +30,468 @@ pub async fn get_log_info() -> Result<system::StorageCategory, String> {
 #[tauri::command]
 pub async fn clean_logs() -> Result<CleanupResult, String> {
     tokio::task::spawn_blocking(|| {
-        // Try sudo -n first (non-interactive, fails fast if no passwordless sudo)
-        let output = std::process::Command::new("sudo")
-            .args(["-n", "journalctl", "--vacuum-size=100M"])
-            .output();
-
-        match output {
-            Ok(out) => {
-                if out.status.success() {
-                    Ok(CleanupResult {
-                        success: true,
-                        space_freed: 0,
-                        message: String::from_utf8_lossy(&out.stdout).to_string(),
-                    })
+        let journal = vec![std::path::PathBuf::from("/var/log/journal")];
+        cleaners::measure_cleanup(&journal, vacuum_journal)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+fn vacuum_journal() -> Result<CleanupResult, String> {
+    // Try sudo -n first (non-interactive, fails fast if no passwordless sudo)
+    let output = std::process::Command::new("sudo")
+        .args(["-n", "journalctl", "--vacuum-size=100M"])
+        .output();
+
+    match output {
+        Ok(out) => {
+            if out.status.success() {
+                Ok(CleanupResult {
+                    success: true,
+                    space_freed: 0,
+                    message: String::from_utf8_lossy(&out.stdout).to_string(),
+                })
+            } else {
+                let stderr = String::from_utf8_lossy(&out.stderr);
+                if stderr.contains("password") || stderr.contains("a password is required") {
+                    Err("Log cleanup requires elevated privileges. \
+                         Please configure passwordless sudo for journalctl, \
+                         or run: sudo journalctl --vacuum-size=100M"
+                        .to_string())
                 } else {
-                    let stderr = String::from_utf8_lossy(&out.stderr);
-                    if stderr.contains("password") || stderr.contains("a password is required") {
-                        Err("Log cleanup requires elevated privileges. \
-                             Please configure passwordless sudo for journalctl, \
-                             or run: sudo journalctl --vacuum-size=100M"
-                            .to_string())
-                    } else {
-                        Err(format!("journalctl vacuum failed: {stderr}"))
-                    }
+                    Err(format!("journalctl vacuum failed: {stderr}"))
                 }
             }
-            Err(e) => Err(format!(
-                "Failed to run journalctl vacuum. \
-                 Make sure sudo is installed and journalctl is available: {e}"
-            )),
         }
+        Err(e) => Err(format!(
+            "Failed to run journalctl vacuum. \
+             Make sure sudo is installed and journalctl is available: {e}"
+        )),
+    }
+}
+
+#[tauri::command]
+pub async fn get_temp_usage(days: Option<u64>) -> Result<Vec<TempLocation>, String> {
+    let days = days.unwrap_or(temp_files::DEFAULT_MAX_AGE_DAYS);
+    tokio::task::spawn_blocking(move || temp_files::get_temp_usage(days))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Removes temp files and Trash items older than `days`; `privileged` hands
+/// `/tmp` and `/var/tmp` to `systemd-tmpfiles --clean` instead.
+#[tauri::command]
+pub async fn clean_temp_files(
+    days: Option<u64>,
+    privileged: Option<bool>,
+) -> Result<CleanupResult, String> {
+    let days = days.unwrap_or(temp_files::DEFAULT_MAX_AGE_DAYS);
+    tokio::task::spawn_blocking(move || {
+        temp_files::clean_temp_files(days, privileged.unwrap_or(false))
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
+#[tauri::command]
+pub async fn get_trash_contents() -> Result<Vec<TrashDir>, String> {
+    tokio::task::spawn_blocking(trash::get_trash_contents)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Empties the Trash on every mount, or only items deleted at least
+/// `older_than_days` ago.
+#[tauri::command]
+pub async fn empty_trash(older_than_days: Option<u64>) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || trash::empty_trash(older_than_days))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn restore_trash_item(path: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || trash::restore_trash_item(&path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
 #[tauri::command]
 pub async fn get_browser_caches() -> Result<Vec<PackageCacheInfo>, String> {
-    tokio::task::spawn_blocking(caches::get_browser_caches)
+    tokio::task::spawn_blocking(browsers::get_browser_caches)
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
+/// Cleans the given data categories of a browser (disk and code cache by default).
+///
+/// A running browser is handled according to `when_running`; a queued
+/// cleanup reports its result through the `browser-cleanup-completed` event.
 #[tauri::command]
-pub async fn clean_browser_cache(browser: String) -> Result<CleanupResult, String> {
+pub async fn clean_browser_cache(
+    app: tauri::AppHandle,
+    browser: String,
+    categories: Option<Vec<DataCategory>>,
+    when_running: Option<RunningPolicy>,
+) -> Result<CleanupResult, String> {
     tokio::task::spawn_blocking(move || {
-        let home = dirs::home_dir().unwrap_or_default();
-
-        // Find actual cache directories for each browser
-        let cache_dirs = match browser.as_str() {
-            "Google Chrome" => find_chrome_caches(&home.join(".cache/google-chrome")),
-            "Brave" => find_chrome_caches(&home.join(".cache/BraveSoftware")),
-            "Chromium" => find_chrome_caches(&home.join(".cache/chromium")),
-            "Firefox" => find_firefox_caches(&home.join(".cache/mozilla/firefox")),
-            _ => return Err(format!("Unknown browser: {browser}")),
-        };
-
-        if cache_dirs.is_empty() {
-            return Ok(CleanupResult {
-                success: true,
-                space_freed: 0,
-                message: format!("No cache found for {browser}"),
-            });
-        }
+        let install = browsers::find_install(&browser)?;
+        let categories = categories.unwrap_or_else(|| DataCategory::DEFAULT_CLEAN.to_vec());
+        run_browser_cleanup(
+            app,
+            install,
+            None,
+            categories,
+            when_running.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
 
-        let mut total_freed: u64 = 0;
-        let mut messages: Vec<String> = Vec::new();
+#[tauri::command]
+pub async fn get_browser_profiles() -> Result<Vec<BrowserProfileInfo>, String> {
+    tokio::task::spawn_blocking(browsers::get_browser_profiles)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
 
-        for dir in &cache_dirs {
-            if dir.exists() {
-                let size = system::dir_size_and_count(dir).0;
-                match std::fs::remove_dir_all(dir) {
-                    Ok(_) => {
-                        total_freed += size;
-                        messages.push(format!("Cleaned {}", dir.display()));
-                    }
-                    Err(e) => {
-                        messages.push(format!("Failed to clean {}: {e}", dir.display()));
-                    }
-                }
-            }
-        }
+/// Like [`clean_browser_cache`], limited to one profile.
+#[tauri::command]
+pub async fn clean_browser_profile(
+    app: tauri::AppHandle,
+    browser: String,
+    profile_id: String,
+    categories: Option<Vec<DataCategory>>,
+    when_running: Option<RunningPolicy>,
+) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let install = browsers::find_install(&browser)?;
+        let profile = install.find_profile(&profile_id)?;
+        let categories = categories.unwrap_or_else(|| DataCategory::DEFAULT_CLEAN.to_vec());
+        run_browser_cleanup(
+            app,
+            install,
+            Some(profile),
+            categories,
+            when_running.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
 
-        Ok(CleanupResult {
-            success: total_freed > 0,
-            space_freed: total_freed,
-            message: messages.join("; "),
-        })
+#[tauri::command]
+pub async fn get_running_browsers() -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(|| {
+        Ok(browsers::discover_installs()
+            .into_iter()
+            .filter(|i| i.is_running())
+            .map(|i| i.display_name)
+            .collect())
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))?
 }
 
-fn find_chrome_caches(profile_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
-    let mut caches = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(profile_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-                // Match Default, Profile 1, Profile 2, etc.
-                if name == "Default" || name.starts_with("Profile ") {
-                    caches.push(path.join("Cache"));
-                    caches.push(path.join("Code Cache"));
+/// How often a queued browser cleanup checks whether the browser has exited.
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// A queued browser cleanup is dropped when the browser is still running
+/// after this long.
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Browser (and profile) keys with a cleanup waiting for the browser to exit,
+/// and the categories it will clean. Later requests for the same key add
+/// their categories to the waiting cleanup.
+static QUEUED_CLEANUPS: Mutex<Vec<(String, Vec<DataCategory>)>> = Mutex::new(Vec::new());
+
+fn run_browser_cleanup(
+    app: tauri::AppHandle,
+    install: BrowserInstall,
+    profile: Option<BrowserProfile>,
+    categories: Vec<DataCategory>,
+    policy: RunningPolicy,
+) -> Result<CleanupResult, String> {
+    if !install.is_running() {
+        return browsers::clean_categories(&install, profile.as_ref(), &categories);
+    }
+    let name = install.display_name.clone();
+    match policy {
+        RunningPolicy::Refuse => Err(format!(
+            "{name} is running. Close it before cleaning its cache, \
+             or queue the cleanup to run when it exits."
+        )),
+        RunningPolicy::Warn => {
+            let mut result = browsers::clean_categories(&install, profile.as_ref(), &categories)?;
+            result.message = format!(
+                "Warning: {name} was running during cleanup; restart it if pages \
+                 misbehave. {}",
+                result.message
+            );
+            Ok(result)
+        }
+        RunningPolicy::Queue => {
+            let key = queue_key(&name, profile.as_ref().map(|p| p.id.as_str()));
+            let mut queued = QUEUED_CLEANUPS
+                .lock()
+                .map_err(|e| format!("Lock error: {e}"))?;
+            let message = match queued.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, pending)) => {
+                    for category in categories {
+                        if !pending.contains(&category) {
+                            pending.push(category);
+                        }
+                    }
+                    format!(
+                        "{name} is running; a cleanup is already queued and will also \
+                         clean the selected data when it exits"
+                    )
                 }
-            }
+                None => {
+                    queued.push((key.clone(), categories));
+                    std::thread::spawn(move || clean_after_exit(app, install, profile, key));
+                    format!("{name} is running; its cache will be cleaned when it exits")
+                }
+            };
+            Ok(CleanupResult {
+                success: true,
+                space_freed: 0,
+                message,
+            })
         }
     }
-    caches
-}
-
-fn find_firefox_caches(base_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
-    let mut caches = Vec::new();
-    if let Ok(entries) = std::fs::read_dir(base_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-                // Firefox profiles end with .default or .default-release
-                if name.ends_with(".default")
-                    || name.ends_with(".default-release")
-                    || name.contains(".default-")
-                {
-                    caches.push(path.join("cache2"));
-                }
-            }
+}
+
+fn queue_key(browser: &str, profile_id: Option<&str>) -> String {
+    match profile_id {
+        Some(id) => format!("{browser}/{id}"),
+        None => browser.to_string(),
+    }
+}
+
+/// Removes the queued cleanup of `key`, returning its categories.
+fn take_queued(key: &str) -> Option<Vec<DataCategory>> {
+    let mut queued = QUEUED_CLEANUPS.lock().ok()?;
+    let index = queued.iter().position(|(k, _)| k == key)?;
+    Some(queued.remove(index).1)
+}
+
+fn is_queued(key: &str) -> bool {
+    QUEUED_CLEANUPS
+        .lock()
+        .is_ok_and(|queued| queued.iter().any(|(k, _)| k == key))
+}
+
+fn clean_after_exit(
+    app: tauri::AppHandle,
+    install: BrowserInstall,
+    profile: Option<BrowserProfile>,
+    key: String,
+) {
+    let deadline = Instant::now() + QUEUE_TIMEOUT;
+    while install.is_running() {
+        if !is_queued(&key) {
+            log::info!("Queued cleanup of {key} was cancelled");
+            return;
+        }
+        if Instant::now() >= deadline {
+            take_queued(&key);
+            let result = CleanupResult {
+                success: false,
+                space_freed: 0,
+                message: format!(
+                    "{} is still running; the queued cleanup was dropped",
+                    install.display_name
+                ),
+            };
+            emit_queued_result(&app, &key, &result);
+            return;
         }
+        std::thread::sleep(QUEUE_POLL_INTERVAL);
+    }
+    let Some(categories) = take_queued(&key) else {
+        log::info!("Queued cleanup of {key} was cancelled");
+        return;
+    };
+    let result = browsers::clean_categories(&install, profile.as_ref(), &categories)
+        .unwrap_or_else(|e| CleanupResult {
+            success: false,
+            space_freed: 0,
+            message: e,
+        });
+    emit_queued_result(&app, &key, &result);
+}
+
+fn emit_queued_result(app: &tauri::AppHandle, key: &str, result: &CleanupResult) {
+    log::info!("Queued cleanup of {key} finished: {}", result.message);
+    if let Err(e) = app.emit("browser-cleanup-completed", result) {
+        log::warn!("Failed to emit browser-cleanup-completed: {e}");
     }
-    caches
+}
+
+/// Cancels the cleanup queued for `browser` (or one of its profiles) while
+/// it was running.
+#[tauri::command]
+pub async fn cancel_queued_browser_cleanup(
+    browser: String,
+    profile_id: Option<String>,
+) -> Result<(), String> {
+    let key = queue_key(&browser, profile_id.as_deref());
+    take_queued(&key)
+        .map(|_| ())
+        .ok_or_else(|| format!("No cleanup is queued for {key}"))
+}
+
+#[tauri::command]
+pub async fn get_browser_data_categories(browser: String) -> Result<Vec<CategorySize>, String> {
+    tokio::task::spawn_blocking(move || Ok(browsers::find_install(&browser)?.category_sizes()))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn audit_cache_dir() -> Result<Vec<CacheEntry>, String> {
+    tokio::task::spawn_blocking(cache_audit::audit_cache_dir)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn clean_cache_entries(names: Vec<String>) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || cache_audit::clean_cache_entries(&names))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Removes `~/.cache` entries untouched for `days` days (safe ones only
+/// unless `include_caution` or `include_unknown` is set).
+#[tauri::command]
+pub async fn clean_stale_cache_entries(
+    days: u64,
+    include_caution: Option<bool>,
+    include_unknown: Option<bool>,
+) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || {
+        cache_audit::clean_stale_cache_entries(
+            days,
+            include_caution.unwrap_or(false),
+            include_unknown.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn get_sandboxed_apps() -> Result<SandboxedAppsReport, String> {
+    tokio::task::spawn_blocking(sandboxed_apps::get_sandboxed_apps)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn remove_unused_flatpak_runtimes() -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(sandboxed_apps::remove_unused_flatpak_runtimes)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn remove_disabled_snap_revisions() -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(sandboxed_apps::remove_disabled_snap_revisions)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Removes the `~/.var/app` and `~/snap` data of the uninstalled apps
+/// `app_ids`.
+#[tauri::command]
+pub async fn clean_orphaned_app_data(app_ids: Vec<String>) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || sandboxed_apps::clean_orphaned_app_data(&app_ids))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Orphaned packages and leftover `~/.config` / `~/.local/share` directories.
+#[tauri::command]
+pub async fn find_orphans() -> Result<OrphanReport, String> {
+    tokio::task::spawn_blocking(orphans::find_orphans)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn get_cargo_cache_summary() -> Result<CargoCacheSummary, String> {
+    tokio::task::spawn_blocking(cargo_cache::get_cargo_cache_summary)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Lists Cargo `target/` directories under `roots` (the home directory by default).
+#[tauri::command]
+pub async fn find_cargo_target_dirs(
+    roots: Option<Vec<String>>,
+) -> Result<Vec<CargoTargetDir>, String> {
+    let roots = roots.filter(|r| !r.is_empty()).unwrap_or_else(|| {
+        vec![dirs::home_dir()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()]
+    });
+    tokio::task::spawn_blocking(move || cargo_cache::find_target_dirs(&roots))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn clean_cargo_target_dirs(paths: Vec<String>) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || cargo_cache::clean_target_dirs(&paths))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Keeps the `keep` newest versions of each crate (one by default).
+#[tauri::command]
+pub async fn prune_cargo_registry(keep: Option<usize>) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || cargo_cache::prune_registry(keep.unwrap_or(1)))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn clean_cargo_git_checkouts() -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(cargo_cache::clean_git_checkouts)
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn find_project_artifacts(
+    root: String,
+    kinds: Option<Vec<String>>,
+) -> Result<Vec<ProjectArtifact>, String> {
+    tokio::task::spawn_blocking(move || artifacts::find_project_artifacts(&root, kinds.as_deref()))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn clean_project_artifacts(paths: Vec<String>) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || artifacts::clean_project_artifacts(&paths))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Removes artifacts of projects under `root` untouched for `days` days.
+#[tauri::command]
+pub async fn sweep_inactive_projects(
+    root: String,
+    days: u64,
+    kinds: Option<Vec<String>>,
+) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || {
+        artifacts::sweep_inactive_projects(&root, days, kinds.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
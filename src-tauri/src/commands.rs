@@ -4,21 +4,32 @@
 //! the frontend via `invoke()`. Commands are grouped by domain: file ops,
 //! system info, AI operations, credentials, and settings.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::ai_client::{AIAnalysis, AIClient, CleanupRecommendation};
+use crate::ai_providers::{self, ProviderEndpoint};
 use crate::caches;
+use crate::candidates;
+use crate::classifier;
+use crate::container_runtime;
 use crate::credentials;
 use crate::database::Database;
 use crate::docker;
+use crate::docker_compose;
+use crate::docker_prune;
 use crate::filesystem::{self, FileInfo};
 use crate::models;
+use crate::path_safety::{self, PathPolicyInfo};
+use crate::recommendations;
 use crate::system::{self, CleanupResult, PackageCacheInfo, StorageBreakdown, SystemInfo};
 
 /// Shared application state managed by Tauri.
 pub struct AppState {
     /// Thread-safe handle to the SQLite database.
     pub db: Arc<Database>,
+    /// Recommendations from the last `get_cleanup_recommendations` call,
+    /// which `apply_recommendation` looks up by ID.
+    pub recommendations: Mutex<Vec<CleanupRecommendation>>,
 }
 
 // --- File operations ---
@@ -88,8 +99,17 @@ pub async fn get_storage_breakdown() -> Result<StorageBreakdown, String> {
 }
 
 #[tauri::command]
-pub async fn get_docker_info() -> Result<docker::DockerInfo, String> {
-    tokio::task::spawn_blocking(docker::get_docker_info)
+pub async fn get_container_runtimes() -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(|| Ok(container_runtime::available_runtimes()))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// `runtime` is `docker`, `podman` or `nerdctl`; the first available one is
+/// used when omitted.
+#[tauri::command]
+pub async fn get_docker_info(runtime: Option<String>) -> Result<docker::DockerInfo, String> {
+    tokio::task::spawn_blocking(move || docker::get_docker_info(runtime.as_deref()))
         .await
         .map_err(|e| format!("Task join error: {e}"))?
 }
@@ -98,10 +118,47 @@ pub async fn get_docker_info() -> Result<docker::DockerInfo, String> {
 pub async fn clean_docker(
     target: String,
     ids: Option<Vec<String>>,
+    runtime: Option<String>,
 ) -> Result<CleanupResult, String> {
-    tokio::task::spawn_blocking(move || docker::clean_docker(&target, ids.as_deref()))
-        .await
-        .map_err(|e| format!("Task join error: {e}"))?
+    tokio::task::spawn_blocking(move || {
+        docker::clean_docker(&target, ids.as_deref(), runtime.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Prunes `target` with age, label, retention and compose-file filters
+/// instead of removing everything unused.
+#[tauri::command]
+pub async fn prune_docker(
+    target: String,
+    policy: docker_prune::PrunePolicy,
+    runtime: Option<String>,
+) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || {
+        docker_prune::prune_docker(&target, &policy, runtime.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Removes a compose project's containers, volumes and networks. Running
+/// projects are only torn down with `force`.
+#[tauri::command]
+pub async fn teardown_compose_project(
+    project: String,
+    force: Option<bool>,
+    runtime: Option<String>,
+) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || {
+        docker_compose::teardown_compose_project(
+            &project,
+            force.unwrap_or(false),
+            runtime.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
 #[tauri::command]
@@ -118,6 +175,13 @@ pub async fn clean_package_cache(manager: String) -> Result<CleanupResult, Strin
         .map_err(|e| format!("Task join error: {e}"))?
 }
 
+#[tauri::command]
+pub async fn preview_package_cache_cleanup(manager: String) -> Result<CleanupResult, String> {
+    tokio::task::spawn_blocking(move || caches::preview_package_cache_cleanup(&manager))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
 // --- Model discovery ---
 
 #[tauri::command]
@@ -125,18 +189,62 @@ pub async fn fetch_available_models() -> Result<String, String> {
     models::fetch_openrouter_models().await
 }
 
+/// Lists the models served at `provider`'s endpoint. `base_url` and
+/// `api_key` override the saved ones so an endpoint can be tried before it
+/// is saved.
+#[tauri::command]
+pub async fn list_provider_models(
+    provider: String,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let connection = ai_providers::connection(&state.db, &provider, base_url, api_key)?;
+    models::list_provider_models(&connection).await
+}
+
+#[tauri::command]
+pub async fn get_provider_endpoint(
+    provider: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProviderEndpoint, String> {
+    ai_providers::spec(&provider)?;
+    Ok(ai_providers::load_endpoint(&state.db, &provider))
+}
+
+#[tauri::command]
+pub async fn set_provider_endpoint(
+    provider: String,
+    endpoint: ProviderEndpoint,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    ai_providers::save_endpoint(&state.db, &provider, endpoint)
+}
+
 // --- AI operations ---
 
+/// Builds a client for `provider` from its saved endpoint. The arguments
+/// override the saved base URL, the keyring and the saved model.
+fn ai_client(
+    db: &Database,
+    provider: &str,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+) -> Result<AIClient, String> {
+    let connection = ai_providers::connection(db, provider, base_url, api_key)?;
+    let model = ai_providers::model(db, provider, model)?;
+    Ok(AIClient::new(connection, &model))
+}
+
 #[tauri::command]
 pub async fn chat_with_ai(
     provider: String,
     message: String,
     model: Option<String>,
+    state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
-    let api_key = credentials::get_credential(&provider)
-        .map_err(|_| format!("No API key saved for {provider}. Configure it in Settings."))?;
-    let m = model.unwrap_or_else(|| get_default_model(&provider));
-    let client = AIClient::new(&provider, &api_key, &m);
+    let client = ai_client(&state.db, &provider, None, None, model)?;
     client.chat(&message).await
 }
 
@@ -145,65 +253,105 @@ pub async fn test_ai_connection(
     provider: String,
     api_key: Option<String>,
     model: String,
+    base_url: Option<String>,
+    state: tauri::State<'_, AppState>,
 ) -> Result<bool, String> {
-    // Use provided key if given, otherwise read from keyring
-    let key = match api_key {
-        Some(k) if !k.is_empty() => k,
-        _ => credentials::get_credential(&provider)
-            .map_err(|_| format!("No API key provided or saved for {provider}"))?,
-    };
-    let client = AIClient::new(&provider, &key, &model);
+    let client = ai_client(&state.db, &provider, base_url, api_key, Some(model))?;
     client.test_connection().await
 }
 
 /// Maximum number of files to analyze concurrently per batch.
 const AI_ANALYSIS_CHUNK_SIZE: usize = 5;
 
+/// Classifies files with the rule-based classifier and sends only the ones
+/// it is unsure about to `provider`. When `provider` is not configured (no
+/// API key for a cloud provider, no model for a local one), every file is
+/// classified by the rules.
 #[tauri::command]
 pub async fn analyze_files_with_ai(
     provider: String,
     file_paths: Vec<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<AIAnalysis>, String> {
-    let api_key = credentials::get_credential(&provider)?;
-    let model = get_default_model(&provider);
-    let client = AIClient::new(&provider, &api_key, &model);
+    let client = match ai_client(&state.db, &provider, None, None, None) {
+        Ok(client) => Some(client),
+        Err(e) => {
+            log::info!("Classifying files offline: {e}");
+            None
+        }
+    };
 
-    let mut analyses = Vec::new();
+    let mut analyses = Vec::with_capacity(file_paths.len());
+    let mut ambiguous: Vec<(usize, FileInfo)> = Vec::new();
+    for path in &file_paths {
+        let file_info = match filesystem::get_file_info(path) {
+            Ok(fi) => fi,
+            Err(e) => {
+                analyses.push(fallback_analysis(path, &e));
+                continue;
+            }
+        };
+        let classification = classifier::classify(&file_info);
+        if client.is_some() && !classification.confident {
+            ambiguous.push((analyses.len(), file_info));
+        } else {
+            save_analysis(&state.db, &file_info, &classification.analysis);
+        }
+        analyses.push(classification.analysis);
+    }
+
+    let Some(client) = client else {
+        return Ok(analyses);
+    };
     // Process files in chunks to avoid overwhelming the API while still
-    // getting some parallelism. Each chunk runs concurrently.
-    for chunk in file_paths.chunks(AI_ANALYSIS_CHUNK_SIZE) {
+    // getting some parallelism. Each chunk runs concurrently. A file whose
+    // analysis fails keeps its rule-based result.
+    for chunk in ambiguous.chunks(AI_ANALYSIS_CHUNK_SIZE) {
         let futures: Vec<_> = chunk
             .iter()
-            .map(|path| analyze_single_file(&client, &state, path))
+            .map(|(_, file_info)| client.analyze_file(file_info))
             .collect();
         let results = futures::future::join_all(futures).await;
-        analyses.extend(results);
+        for ((index, file_info), result) in chunk.iter().zip(results) {
+            match result {
+                Ok(analysis) => {
+                    save_analysis(&state.db, file_info, &analysis);
+                    analyses[*index] = analysis;
+                }
+                Err(e) => {
+                    log::warn!("Failed to analyze {}: {e}", file_info.path);
+                    save_analysis(&state.db, file_info, &analyses[*index]);
+                }
+            }
+        }
     }
     Ok(analyses)
 }
 
-async fn analyze_single_file(
-    client: &AIClient,
-    state: &tauri::State<'_, AppState>,
-    path: &str,
-) -> AIAnalysis {
-    let file_info = match filesystem::get_file_info(path) {
-        Ok(fi) => fi,
-        Err(e) => return fallback_analysis(path, &e),
-    };
-    match client.analyze_file(&file_info).await {
-        Ok(a) => {
-            let _ = state.db.update_file_analysis(
-                &file_info.id,
-                Some(&a.category),
-                Some(a.importance_score),
-                Some(&a.reason),
-            );
-            a
-        }
-        Err(e) => fallback_analysis(path, &e),
-    }
+/// Classifies files with the rule-based classifier only; nothing leaves the
+/// machine.
+#[tauri::command]
+pub async fn classify_files(file_paths: Vec<String>) -> Result<Vec<AIAnalysis>, String> {
+    tokio::task::spawn_blocking(move || {
+        file_paths
+            .iter()
+            .map(|path| match filesystem::get_file_info(path) {
+                Ok(file_info) => classifier::classify(&file_info).analysis,
+                Err(e) => fallback_analysis(path, &e),
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))
+}
+
+fn save_analysis(db: &Database, file_info: &FileInfo, analysis: &AIAnalysis) {
+    let _ = db.update_file_analysis(
+        &file_info.id,
+        Some(&analysis.category),
+        Some(analysis.importance_score),
+        Some(&analysis.reason),
+    );
 }
 
 fn fallback_analysis(path: &str, error: &str) -> AIAnalysis {
@@ -221,33 +369,55 @@ fn fallback_analysis(path: &str, error: &str) -> AIAnalysis {
 #[tauri::command]
 pub async fn get_cleanup_recommendations(
     provider: String,
+    state: tauri::State<'_, AppState>,
 ) -> Result<Vec<CleanupRecommendation>, String> {
-    let api_key = credentials::get_credential(&provider)?;
-    let model = get_default_model(&provider);
-    let client = AIClient::new(&provider, &api_key, &model);
+    let client = ai_client(&state.db, &provider, None, None, None)?;
 
     let sys_info = system::get_system_info()?;
     let storage = system::get_storage_breakdown()?;
-    let pkg_caches = caches::get_package_caches()?;
+    let scan_dirs = candidates::scan_dirs(&state.db);
+    let candidates =
+        tokio::task::spawn_blocking(move || candidates::collect_candidates(&scan_dirs))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))?;
 
     let stats = serde_json::to_string_pretty(&serde_json::json!({
         "system": sys_info,
         "storage": storage,
-        "package_caches": pkg_caches,
+        "candidates": candidates,
     }))
     .map_err(|e| format!("Serialize error: {e}"))?;
 
-    client.get_recommendations(&stats).await
+    let recommendations =
+        candidates::ground(client.get_recommendations(&stats).await?, &candidates);
+    *state
+        .recommendations
+        .lock()
+        .map_err(|e| format!("Lock error: {e}"))? = recommendations.clone();
+    Ok(recommendations)
 }
 
-fn get_default_model(provider: &str) -> String {
-    match provider {
-        "openai" => "gpt-4o-mini".to_string(),
-        "gemini" => "gemini-2.0-flash".to_string(),
-        "claude" => "claude-sonnet-4-20250514".to_string(),
-        "kimi" => "moonshot-v1-8k".to_string(),
-        _ => "gpt-4o-mini".to_string(),
-    }
+/// Runs the selected items of a recommendation from the last
+/// `get_cleanup_recommendations` call after validating each one.
+#[tauri::command]
+pub async fn apply_recommendation(
+    id: String,
+    selected_items: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<CleanupResult, String> {
+    let rec = state
+        .recommendations
+        .lock()
+        .map_err(|e| format!("Lock error: {e}"))?
+        .iter()
+        .find(|r| r.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown recommendation: {id}"))?;
+    tokio::task::spawn_blocking(move || {
+        recommendations::apply_recommendation(&rec, &selected_items)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
 // --- Credential operations ---
@@ -288,5 +458,11 @@ pub async fn set_setting(
     value: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
+    path_safety::apply_setting(&key, &value)?;
     state.db.set_setting(&key, &value)
 }
+
+#[tauri::command]
+pub async fn get_path_policy() -> Result<PathPolicyInfo, String> {
+    Ok(path_safety::get_path_policy())
+}
@@ -0,0 +1,143 @@
+//! Container runtime detection: Docker, Podman and nerdctl (containerd).
+//!
+//! Docker and Podman both serve the Docker Engine API on a Unix socket, so
+//! they are queried through [`DockerApi`] when their socket answers. Podman's
+//! socket is usually socket-activated and may be off, and nerdctl has no API
+//! socket at all; those fall back to the runtime's CLI, whose `images`, `ps`,
+//! `volume` and `prune` subcommands all follow the Docker CLI.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::docker_api::{DockerApi, DEFAULT_SOCKET};
+use crate::system;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    Docker,
+    Podman,
+    Nerdctl,
+}
+
+impl RuntimeKind {
+    pub const ALL: [RuntimeKind; 3] = [
+        RuntimeKind::Docker,
+        RuntimeKind::Podman,
+        RuntimeKind::Nerdctl,
+    ];
+
+    pub fn binary(self) -> &'static str {
+        match self {
+            RuntimeKind::Docker => "docker",
+            RuntimeKind::Podman => "podman",
+            RuntimeKind::Nerdctl => "nerdctl",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        RuntimeKind::ALL
+            .into_iter()
+            .find(|k| k.binary() == name)
+            .ok_or_else(|| format!("Unknown container runtime: {name}"))
+    }
+
+    /// API sockets to try, most specific first. Rootless sockets come before
+    /// rootful ones, which an unprivileged user usually cannot open.
+    fn sockets(self) -> Vec<PathBuf> {
+        let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .or_else(|| system::current_uid().map(|uid| PathBuf::from(format!("/run/user/{uid}"))));
+        let from_env = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .and_then(|host| host.strip_prefix("unix://").map(PathBuf::from))
+        };
+        let mut sockets = Vec::new();
+        match self {
+            RuntimeKind::Docker => {
+                sockets.extend(from_env("DOCKER_HOST"));
+                sockets.extend(runtime_dir.map(|d| d.join("docker.sock")));
+                sockets.push(PathBuf::from(DEFAULT_SOCKET));
+            }
+            RuntimeKind::Podman => {
+                sockets.extend(from_env("CONTAINER_HOST"));
+                sockets.extend(runtime_dir.map(|d| d.join("podman/podman.sock")));
+                sockets.push(PathBuf::from("/run/podman/podman.sock"));
+            }
+            RuntimeKind::Nerdctl => {}
+        }
+        sockets
+    }
+}
+
+/// A usable runtime: its CLI, plus its API when the socket answers.
+#[derive(Debug, Clone)]
+pub struct ContainerRuntime {
+    pub kind: RuntimeKind,
+    pub api: Option<DockerApi>,
+}
+
+impl ContainerRuntime {
+    /// Connects to `kind`, or returns `None` when neither its API nor its CLI
+    /// can reach a running engine.
+    pub fn connect(kind: RuntimeKind) -> Option<Self> {
+        let api = kind
+            .sockets()
+            .into_iter()
+            .filter(|s| s.exists())
+            .map(DockerApi::new)
+            .find(DockerApi::ping);
+        if api.is_some() || cli_available(kind) {
+            Some(ContainerRuntime { kind, api })
+        } else {
+            None
+        }
+    }
+
+    /// The named runtime, or the first available one (Docker, then Podman,
+    /// then nerdctl) when `name` is `None`.
+    pub fn resolve(name: Option<&str>) -> Result<Self, String> {
+        match name {
+            Some(name) => {
+                let kind = RuntimeKind::parse(name)?;
+                ContainerRuntime::connect(kind)
+                    .ok_or_else(|| format!("{name} is not installed or not running"))
+            }
+            None => RuntimeKind::ALL
+                .into_iter()
+                .find_map(ContainerRuntime::connect)
+                .ok_or_else(|| {
+                    "No container runtime found. Install and start Docker, Podman or nerdctl"
+                        .to_string()
+                }),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.kind.binary()
+    }
+
+    /// A `Command` for the runtime's CLI.
+    pub fn command(&self) -> Command {
+        Command::new(self.kind.binary())
+    }
+}
+
+fn cli_available(kind: RuntimeKind) -> bool {
+    Command::new(kind.binary())
+        .arg("info")
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+/// Names of the runtimes that are installed and running.
+pub fn available_runtimes() -> Vec<String> {
+    RuntimeKind::ALL
+        .into_iter()
+        .filter_map(ContainerRuntime::connect)
+        .map(|rt| rt.name().to_string())
+        .collect()
+}
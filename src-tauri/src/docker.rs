@@ -1,6 +1,14 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
-use std::process::Command;
 
+use crate::container_runtime::{ContainerRuntime, RuntimeKind};
+use crate::docker_api::{
+    ApiContainer, ApiDiskUsage, ApiImage, ApiMount, ApiNetwork, ApiVolumeList, DockerApi,
+};
+use crate::docker_compose::{self, ComposeProject, PROJECT_LABEL};
+use crate::docker_prune::{self, PrunePolicy};
+use crate::docker_usage::{self, BuildCacheRecord, DiskUsageCategory, UsageCategory};
 use crate::system::CleanupResult;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -10,7 +18,22 @@ pub struct DockerImage {
     pub id: String,
     pub size: u64,
     pub created: String,
+    /// Whether any container, running or stopped, uses the image.
     pub in_use: bool,
+    pub usage: ImageUsage,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageUsage {
+    /// Used by at least one running container.
+    Running,
+    /// Used only by stopped containers, which must be removed first.
+    StoppedOnly,
+    /// Tagged but not used by any container.
+    Unused,
+    /// Untagged and not used by any container.
+    Dangling,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,53 +50,302 @@ pub struct DockerVolume {
     pub name: String,
     pub driver: String,
     pub size: u64,
+    /// Whether any container, running or stopped, mounts the volume.
     pub in_use: bool,
 }
 
+/// What a container keeps alive: its image and the volumes it mounts.
+pub struct ContainerRef {
+    /// Short container ID, as returned by [`short_id`].
+    pub id: String,
+    /// Short image ID.
+    pub image_id: String,
+    pub running: bool,
+    pub volumes: Vec<String>,
+    pub labels: HashMap<String, String>,
+}
+
+impl ContainerRef {
+    fn new(
+        id: &str,
+        image_id: &str,
+        running: bool,
+        mounts: &[ApiMount],
+        labels: Option<&HashMap<String, String>>,
+    ) -> Self {
+        ContainerRef {
+            id: short_id(id),
+            image_id: short_id(image_id),
+            running,
+            labels: labels.cloned().unwrap_or_default(),
+            volumes: mounts
+                .iter()
+                .filter(|m| m.kind == "volume")
+                .filter_map(|m| m.name.clone())
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DockerInfo {
+    /// Runtime the data came from: `docker`, `podman` or `nerdctl`.
+    pub runtime: String,
     pub images: Vec<DockerImage>,
     pub containers: Vec<DockerContainer>,
     pub volumes: Vec<DockerVolume>,
+    /// Containers, volumes, networks and images grouped by compose project.
+    pub compose_projects: Vec<ComposeProject>,
+    /// Size and reclaimable bytes of images, containers, volumes and build
+    /// cache, as `system df` reports them.
+    pub disk_usage: Vec<DiskUsageCategory>,
+    pub build_cache: Vec<BuildCacheRecord>,
     pub build_cache_size: u64,
+    /// Sum of the reclaimable bytes of every category.
     pub total_reclaimable: u64,
 }
 
-pub fn get_docker_info() -> Result<DockerInfo, String> {
-    let docker_check = Command::new("docker")
-        .arg("info")
-        .output()
-        .map_err(|_| "Docker is not installed".to_string())?;
+impl DockerInfo {
+    fn set_totals(&mut self) {
+        self.build_cache_size = self
+            .disk_usage
+            .iter()
+            .filter(|c| c.category == UsageCategory::BuildCache)
+            .map(|c| c.size)
+            .sum();
+        self.total_reclaimable = self.disk_usage.iter().map(|c| c.reclaimable).sum();
+    }
+}
+
+/// Reads images, containers, volumes and disk usage from `runtime` (the
+/// first available one by default) through its Engine API, falling back to
+/// parsing its CLI when no API socket answers.
+pub fn get_docker_info(runtime: Option<&str>) -> Result<DockerInfo, String> {
+    let rt = ContainerRuntime::resolve(runtime)?;
+    let mut info = match &rt.api {
+        Some(api) => get_docker_info_api(api)?,
+        None => {
+            log::debug!("No {} API socket answered, using the CLI", rt.name());
+            get_docker_info_cli(&rt)?
+        }
+    };
+    info.runtime = rt.name().to_string();
+    Ok(info)
+}
+
+fn get_docker_info_api(api: &DockerApi) -> Result<DockerInfo, String> {
+    let images: Vec<ApiImage> = api.get("/images/json")?;
+    let containers: Vec<ApiContainer> = api.get("/containers/json?all=1&size=1")?;
+    let volumes: ApiVolumeList = api.get("/volumes")?;
+    let df: ApiDiskUsage = api.get("/system/df")?;
+    let (disk_usage, build_cache) = docker_usage::from_api(&df);
+    let networks: Vec<ApiNetwork> = api.get("/networks")?;
+
+    let refs: Vec<ContainerRef> = containers
+        .iter()
+        .map(|c| {
+            ContainerRef::new(
+                &c.id,
+                &c.image_id,
+                c.state == "running",
+                &c.mounts,
+                c.labels.as_ref(),
+            )
+        })
+        .collect();
+    let volume_projects = project_labels(
+        volumes
+            .volumes
+            .iter()
+            .flatten()
+            .map(|v| (&v.name, v.labels.as_ref())),
+    );
+    let network_projects = project_labels(networks.iter().map(|n| (&n.name, n.labels.as_ref())));
+    let volume_sizes: HashMap<String, u64> = df
+        .volumes
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|v| {
+            let size = v.usage_data?.size;
+            (size >= 0).then_some((v.name, size as u64))
+        })
+        .collect();
+
+    let mut images: Vec<DockerImage> = images.iter().flat_map(image_rows).collect();
+    let mut volumes: Vec<DockerVolume> = volumes
+        .volumes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|v| DockerVolume {
+            size: volume_sizes.get(&v.name).copied().unwrap_or(0),
+            name: v.name,
+            driver: v.driver,
+            in_use: false,
+        })
+        .collect();
+    mark_usage(&mut images, &mut volumes, &refs);
 
-    if !docker_check.status.success() {
-        return Err("Docker is not running".to_string());
+    let mut info = DockerInfo {
+        runtime: String::new(),
+        images,
+        containers: containers
+            .into_iter()
+            .map(|c| DockerContainer {
+                id: short_id(&c.id),
+                name: c
+                    .names
+                    .first()
+                    .map(|n| n.trim_start_matches('/').to_string())
+                    .unwrap_or_default(),
+                image: c.image,
+                status: c.status,
+                size: c.size_rw.unwrap_or(0).max(0) as u64,
+            })
+            .collect(),
+        volumes,
+        compose_projects: Vec::new(),
+        disk_usage,
+        build_cache,
+        build_cache_size: 0,
+        total_reclaimable: 0,
+    };
+    info.compose_projects =
+        docker_compose::group_projects(&info, &refs, &volume_projects, &network_projects);
+    info.set_totals();
+    Ok(info)
+}
+
+/// Maps each object name to the compose project in its labels, skipping
+/// objects that do not belong to one.
+fn project_labels<'a>(
+    objects: impl Iterator<Item = (&'a String, Option<&'a HashMap<String, String>>)>,
+) -> HashMap<String, String> {
+    objects
+        .filter_map(|(name, labels)| {
+            let project = labels?.get(PROJECT_LABEL)?;
+            Some((name.clone(), project.clone()))
+        })
+        .collect()
+}
+
+/// One row per repository tag, like `docker images`; untagged images get a
+/// single `<none>:<none>` row.
+fn image_rows(image: &ApiImage) -> Vec<DockerImage> {
+    let tags: Vec<(String, String)> = image
+        .repo_tags
+        .iter()
+        .flatten()
+        .filter(|t| *t != "<none>:<none>")
+        .map(|t| match t.rsplit_once(':') {
+            Some((repo, tag)) if !tag.contains('/') => (repo.to_string(), tag.to_string()),
+            _ => (t.clone(), "latest".to_string()),
+        })
+        .collect();
+    let tags = if tags.is_empty() {
+        vec![("<none>".to_string(), "<none>".to_string())]
+    } else {
+        tags
+    };
+    tags.into_iter()
+        .map(|(repository, tag)| DockerImage {
+            repository,
+            tag,
+            id: short_id(&image.id),
+            size: image.size.max(0) as u64,
+            created: created_since(image.created),
+            in_use: false,
+            usage: ImageUsage::Unused,
+        })
+        .collect()
+}
+
+/// Sets image and volume usage from the containers that reference them.
+fn mark_usage(images: &mut [DockerImage], volumes: &mut [DockerVolume], refs: &[ContainerRef]) {
+    for image in images {
+        let id = short_id(&image.id);
+        let users: Vec<&ContainerRef> = refs.iter().filter(|r| r.image_id == id).collect();
+        image.in_use = !users.is_empty();
+        image.usage = if users.iter().any(|r| r.running) {
+            ImageUsage::Running
+        } else if image.in_use {
+            ImageUsage::StoppedOnly
+        } else if image.tag == "<none>" {
+            ImageUsage::Dangling
+        } else {
+            ImageUsage::Unused
+        };
+    }
+    for volume in volumes {
+        volume.in_use = refs.iter().any(|r| r.volumes.contains(&volume.name));
     }
+}
 
-    let images = get_docker_images()?;
-    let containers = get_docker_containers()?;
-    let volumes = get_docker_volumes()?;
-    let (build_cache_size, total_reclaimable) = get_docker_disk_usage()?;
+/// The 12-character form the CLI shows, without the `sha256:` prefix.
+pub fn short_id(id: &str) -> String {
+    let id = id.strip_prefix("sha256:").unwrap_or(id);
+    id.chars().take(12).collect()
+}
+
+/// Relative age like the CLI's `CreatedSince`, e.g. "3 weeks ago".
+pub fn created_since(timestamp: i64) -> String {
+    let secs = (chrono::Utc::now().timestamp() - timestamp).max(0);
+    let (n, unit) = match secs {
+        s if s < 60 => return "Less than a minute ago".to_string(),
+        s if s < 3_600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3_600, "hour"),
+        s if s < 14 * 86_400 => (s / 86_400, "day"),
+        s if s < 60 * 86_400 => (s / (7 * 86_400), "week"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" })
+}
+
+fn get_docker_info_cli(rt: &ContainerRuntime) -> Result<DockerInfo, String> {
+    let mut images = get_docker_images(rt)?;
+    let containers = get_docker_containers(rt)?;
+    let mut volumes = get_docker_volumes(rt)?;
 
-    Ok(DockerInfo {
+    let refs = inspect_containers(rt, &containers)?;
+    let sizes = get_volume_sizes(rt);
+    for volume in &mut volumes {
+        volume.size = sizes.get(&volume.name).copied().unwrap_or(0);
+    }
+    mark_usage(&mut images, &mut volumes, &refs);
+    let volume_projects = labeled_projects_cli(rt, "volume");
+    let network_projects = labeled_projects_cli(rt, "network");
+
+    let mut info = DockerInfo {
+        runtime: String::new(),
         images,
         containers,
         volumes,
-        build_cache_size,
-        total_reclaimable,
-    })
+        compose_projects: Vec::new(),
+        disk_usage: Vec::new(),
+        build_cache: docker_usage::buildx_records(rt),
+        build_cache_size: 0,
+        total_reclaimable: 0,
+    };
+    info.compose_projects =
+        docker_compose::group_projects(&info, &refs, &volume_projects, &network_projects);
+    info.disk_usage =
+        docker_usage::from_cli(rt).unwrap_or_else(|| docker_usage::estimate(&info, &refs));
+    info.set_totals();
+    Ok(info)
 }
 
 fn tab_field(parts: &[&str], index: usize) -> String {
     parts.get(index).unwrap_or(&"").to_string()
 }
 
-fn get_docker_images() -> Result<Vec<DockerImage>, String> {
+fn get_docker_images(rt: &ContainerRuntime) -> Result<Vec<DockerImage>, String> {
     let fmt = "{{.Repository}}\t{{.Tag}}\t{{.ID}}\t\
                {{.Size}}\t{{.CreatedSince}}";
-    let output = Command::new("docker")
+    let output = rt
+        .command()
         .args(["images", "--format", fmt])
         .output()
-        .map_err(|e| format!("docker images: {e}"))?;
+        .map_err(|e| format!("{} images: {e}", rt.name()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let images = stdout
@@ -88,6 +360,7 @@ fn get_docker_images() -> Result<Vec<DockerImage>, String> {
                 size: parse_size_string(&tab_field(&p, 3)),
                 created: tab_field(&p, 4),
                 in_use: false,
+                usage: ImageUsage::Unused,
             }
         })
         .collect();
@@ -95,13 +368,14 @@ fn get_docker_images() -> Result<Vec<DockerImage>, String> {
     Ok(images)
 }
 
-fn get_docker_containers() -> Result<Vec<DockerContainer>, String> {
+fn get_docker_containers(rt: &ContainerRuntime) -> Result<Vec<DockerContainer>, String> {
     let fmt = "{{.ID}}\t{{.Image}}\t{{.Status}}\t\
                {{.Size}}\t{{.Names}}";
-    let output = Command::new("docker")
-        .args(["ps", "-a", "--format", fmt])
+    let output = rt
+        .command()
+        .args(["ps", "-a", "--size", "--format", fmt])
         .output()
-        .map_err(|e| format!("docker ps: {e}"))?;
+        .map_err(|e| format!("{} ps: {e}", rt.name()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let containers = stdout
@@ -122,11 +396,12 @@ fn get_docker_containers() -> Result<Vec<DockerContainer>, String> {
     Ok(containers)
 }
 
-fn get_docker_volumes() -> Result<Vec<DockerVolume>, String> {
-    let output = Command::new("docker")
+fn get_docker_volumes(rt: &ContainerRuntime) -> Result<Vec<DockerVolume>, String> {
+    let output = rt
+        .command()
         .args(["volume", "ls", "--format", "{{.Name}}\t{{.Driver}}"])
         .output()
-        .map_err(|e| format!("docker volume ls: {e}"))?;
+        .map_err(|e| format!("{} volume ls: {e}", rt.name()))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let volumes = stdout
@@ -146,32 +421,164 @@ fn get_docker_volumes() -> Result<Vec<DockerVolume>, String> {
     Ok(volumes)
 }
 
-fn get_docker_disk_usage() -> Result<(u64, u64), String> {
-    let output = Command::new("docker")
-        .args(["system", "df"])
+/// The subset of `<runtime> container inspect` output needed to tell which
+/// images and volumes a container holds on to.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InspectedContainer {
+    id: String,
+    image: String,
+    state: InspectedState,
+    mounts: Vec<ApiMount>,
+    config: InspectedConfig,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InspectedState {
+    running: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InspectedConfig {
+    labels: Option<HashMap<String, String>>,
+}
+
+/// A volume or network from `<runtime> volume|network inspect`. Podman's
+/// network inspect uses lowercase keys.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InspectedObject {
+    #[serde(alias = "name")]
+    name: String,
+    #[serde(alias = "labels")]
+    labels: Option<HashMap<String, String>>,
+}
+
+/// Images and volumes referenced by every container, running or stopped.
+pub fn container_refs(rt: &ContainerRuntime) -> Result<Vec<ContainerRef>, String> {
+    inspect_containers(rt, &get_docker_containers(rt)?)
+}
+
+fn inspect_containers(
+    rt: &ContainerRuntime,
+    containers: &[DockerContainer],
+) -> Result<Vec<ContainerRef>, String> {
+    if containers.is_empty() {
+        return Ok(Vec::new());
+    }
+    let output = rt
+        .command()
+        .args(["container", "inspect"])
+        .args(containers.iter().map(|c| &c.id))
         .output()
-        .map_err(|e| format!("docker system df: {e}"))?;
+        .map_err(|e| format!("{} container inspect: {e}", rt.name()))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} container inspect failed: {}",
+            rt.name(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut build_cache = 0u64;
-    let mut total_reclaimable = 0u64;
-
-    for line in stdout.lines() {
-        if line.starts_with("Build Cache") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 4 {
-                build_cache = parse_size_string(parts[2]);
-                if let Some(r) = parts.last() {
-                    total_reclaimable = parse_size_string(r);
-                }
+    let inspected: Vec<InspectedContainer> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("{} container inspect: invalid JSON: {e}", rt.name()))?;
+    Ok(inspected
+        .iter()
+        .map(|c| {
+            ContainerRef::new(
+                &c.id,
+                &c.image,
+                c.state.running,
+                &c.mounts,
+                c.config.labels.as_ref(),
+            )
+        })
+        .collect())
+}
+
+/// Compose projects of the volumes or networks (`kind`) that carry the
+/// project label. Failures leave those objects ungrouped.
+fn labeled_projects_cli(rt: &ContainerRuntime, kind: &str) -> HashMap<String, String> {
+    let filter = format!("label={PROJECT_LABEL}");
+    let names = match rt
+        .command()
+        .args([kind, "ls", "-q", "--filter", &filter])
+        .output()
+    {
+        Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>(),
+        _ => return HashMap::new(),
+    };
+    if names.is_empty() {
+        return HashMap::new();
+    }
+
+    let inspected: Vec<InspectedObject> =
+        match rt.command().arg(kind).arg("inspect").args(&names).output() {
+            Ok(out) if out.status.success() => {
+                serde_json::from_slice(&out.stdout).unwrap_or_else(|e| {
+                    log::debug!("{} {kind} inspect: invalid JSON: {e}", rt.name());
+                    Vec::new()
+                })
             }
-        }
+            _ => Vec::new(),
+        };
+    project_labels(inspected.iter().map(|o| (&o.name, o.labels.as_ref())))
+}
+
+/// Volume sizes from the "Local Volumes space usage" table of
+/// `system df -v`. Empty when the runtime has no such command (nerdctl) or
+/// it fails; sizes then stay at 0 rather than failing the whole report.
+fn get_volume_sizes(rt: &ContainerRuntime) -> HashMap<String, u64> {
+    if rt.kind == RuntimeKind::Nerdctl {
+        return HashMap::new();
     }
+    let output = match rt.command().args(["system", "df", "-v"]).output() {
+        Ok(out) if out.status.success() => out,
+        Ok(out) => {
+            log::debug!(
+                "{} system df -v failed: {}",
+                rt.name(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+            return HashMap::new();
+        }
+        Err(e) => {
+            log::debug!("{} system df -v: {e}", rt.name());
+            return HashMap::new();
+        }
+    };
+    parse_volume_sizes(&String::from_utf8_lossy(&output.stdout))
+}
 
-    Ok((build_cache, total_reclaimable))
+fn parse_volume_sizes(output: &str) -> HashMap<String, u64> {
+    let mut sizes = HashMap::new();
+    let mut lines = output
+        .lines()
+        .skip_while(|l| !l.starts_with("Local Volumes space usage"))
+        .skip(1)
+        .skip_while(|l| l.trim().is_empty());
+    // Skip the "VOLUME NAME  LINKS  SIZE" header.
+    lines.next();
+    for line in lines.take_while(|l| !l.trim().is_empty()) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if let (Some(name), Some(size)) = (parts.first(), parts.last()) {
+            sizes.insert(name.to_string(), parse_size_string(size));
+        }
+    }
+    sizes
 }
 
-pub fn clean_docker(target: &str, ids: Option<&[String]>) -> Result<CleanupResult, String> {
+pub fn clean_docker(
+    target: &str,
+    ids: Option<&[String]>,
+    runtime: Option<&str>,
+) -> Result<CleanupResult, String> {
     // If specific IDs are provided, remove those individually
     if let Some(ids) = ids {
         if ids.is_empty() {
@@ -181,58 +588,37 @@ pub fn clean_docker(target: &str, ids: Option<&[String]>) -> Result<CleanupResul
                 message: "No items selected".to_string(),
             });
         }
-        return clean_docker_selected(target, ids);
+        let rt = ContainerRuntime::resolve(runtime)?;
+        return clean_docker_selected(&rt, target, ids);
     }
 
-    // Bulk prune based on target
-    let args: Vec<&str> = match target {
-        "images" | "unused_images" => vec!["image", "prune", "-a", "-f"],
-        "containers" | "unused_containers" => vec!["container", "prune", "-f"],
-        "volumes" | "unused_volumes" => vec!["volume", "prune", "-f"],
-        "build-cache" => vec!["builder", "prune", "-a", "-f"],
-        "all" => vec!["system", "prune", "-a", "-f", "--volumes"],
-        _ => return Err(format!("Unknown docker cleanup target: {target}")),
+    // Bulk cleanup goes through the policy-based pruner, which keeps named
+    // volumes and the images compose projects reference.
+    let target = match target {
+        "unused_images" => "images",
+        "unused_containers" => "containers",
+        "unused_volumes" => "volumes",
+        other => other,
     };
-
-    let output = Command::new("docker")
-        .args(&args)
-        .output()
-        .map_err(|e| format!("docker cleanup: {e}"))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
-    if output.status.success() {
-        let space_freed = parse_reclaimed_space(&stdout);
-        Ok(CleanupResult {
-            success: true,
-            space_freed,
-            message: stdout.to_string(),
-        })
-    } else {
-        // Some docker prune commands return exit code 1 when there's nothing to prune
-        if stderr.contains("nothing") || stdout.contains("nothing") {
-            return Ok(CleanupResult {
-                success: true,
-                space_freed: 0,
-                message: "Nothing to clean".to_string(),
-            });
-        }
-        Err(format!("Docker cleanup failed: {stderr}"))
-    }
+    docker_prune::prune_docker(target, &PrunePolicy::default(), runtime)
 }
 
-fn clean_docker_selected(target: &str, ids: &[String]) -> Result<CleanupResult, String> {
+fn clean_docker_selected(
+    rt: &ContainerRuntime,
+    target: &str,
+    ids: &[String],
+) -> Result<CleanupResult, String> {
     let mut total_freed: u64 = 0;
     let mut messages: Vec<String> = Vec::new();
 
     for id in ids {
         let result = match target {
             "images" => {
-                let out = Command::new("docker")
+                let out = rt
+                    .command()
                     .args(["rmi", "-f", id])
                     .output()
-                    .map_err(|e| format!("docker rmi: {e}"))?;
+                    .map_err(|e| format!("{} rmi: {e}", rt.name()))?;
                 if out.status.success() {
                     Ok(parse_reclaimed_space(&String::from_utf8_lossy(&out.stdout)))
                 } else {
@@ -240,10 +626,11 @@ fn clean_docker_selected(target: &str, ids: &[String]) -> Result<CleanupResult,
                 }
             }
             "containers" => {
-                let out = Command::new("docker")
+                let out = rt
+                    .command()
                     .args(["rm", "-f", id])
                     .output()
-                    .map_err(|e| format!("docker rm: {e}"))?;
+                    .map_err(|e| format!("{} rm: {e}", rt.name()))?;
                 if out.status.success() {
                     Ok(0u64)
                 } else {
@@ -251,10 +638,11 @@ fn clean_docker_selected(target: &str, ids: &[String]) -> Result<CleanupResult,
                 }
             }
             "volumes" => {
-                let out = Command::new("docker")
+                let out = rt
+                    .command()
                     .args(["volume", "rm", "-f", id])
                     .output()
-                    .map_err(|e| format!("docker volume rm: {e}"))?;
+                    .map_err(|e| format!("{} volume rm: {e}", rt.name()))?;
                 if out.status.success() {
                     Ok(0u64)
                 } else {
@@ -282,7 +670,7 @@ fn clean_docker_selected(target: &str, ids: &[String]) -> Result<CleanupResult,
     })
 }
 
-fn parse_reclaimed_space(output: &str) -> u64 {
+pub fn parse_reclaimed_space(output: &str) -> u64 {
     for line in output.lines() {
         if line.contains("reclaimed space") {
             if let Some(size_str) = line.split(':').nth(1) {
@@ -293,23 +681,31 @@ fn parse_reclaimed_space(output: &str) -> u64 {
     0
 }
 
-fn parse_size_string(s: &str) -> u64 {
-    let s = s.trim();
-    // Handle sizes like "1.05GB", "24.3MB", "0B", etc.
-    let (num_str, mult) = if let Some((n, _)) = s.split_once("GB") {
-        (n, 1_000_000_000u64)
-    } else if let Some((n, _)) = s.split_once("MB") {
-        (n, 1_000_000u64)
-    } else if let Some((n, _)) = s.split_once("kB") {
-        (n, 1_000u64)
-    } else if let Some((n, _)) = s.split_once("B") {
-        (n, 1u64)
-    } else {
-        (s, 1u64)
+/// Parses CLI sizes such as "1.05GB", "24.3MiB", "512kB" or
+/// "0B (virtual 1.2GB)". Decimal units are powers of 1000, `*iB` units
+/// powers of 1024.
+pub fn parse_size_string(s: &str) -> u64 {
+    let s = s.split('(').next().unwrap_or("").trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num_str, unit) = s.split_at(split);
+    let mult: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "pb" => 1_000_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        "pib" => 1 << 50,
+        _ => return 0,
     };
 
     num_str
-        .trim()
         .parse::<f64>()
         .map(|n| (n * mult as f64) as u64)
         .unwrap_or(0)
@@ -0,0 +1,321 @@
+//! Minimal Docker Engine API client over the daemon's Unix socket.
+//!
+//! Speaks just enough HTTP/1.1 for the daemon's JSON endpoints: one request
+//! per connection (`Connection: close`), with `Content-Length`, chunked and
+//! read-to-EOF bodies. The socket path is chosen by the caller (see
+//! [`crate::container_runtime`]), so Podman's compatible API or a test
+//! server can stand in for the Docker daemon.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+pub const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+
+/// Generous because `/system/df` walks every layer on large hosts.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct DockerApi {
+    socket: PathBuf,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiImage {
+    pub id: String,
+    pub repo_tags: Option<Vec<String>>,
+    pub created: i64,
+    pub size: i64,
+    pub shared_size: i64,
+    /// Number of containers using the image; -1 when not computed.
+    pub containers: i64,
+    pub labels: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiMount {
+    #[serde(rename = "Type")]
+    pub kind: String,
+    pub name: Option<String>,
+    pub source: String,
+    pub destination: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiContainer {
+    pub id: String,
+    pub names: Vec<String>,
+    pub image: String,
+    #[serde(rename = "ImageID")]
+    pub image_id: String,
+    pub created: i64,
+    pub state: String,
+    pub status: String,
+    pub size_rw: Option<i64>,
+    pub size_root_fs: Option<i64>,
+    pub labels: Option<HashMap<String, String>>,
+    pub mounts: Vec<ApiMount>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiVolumeUsage {
+    /// Bytes; -1 when the daemon did not compute it.
+    pub size: i64,
+    pub ref_count: i64,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiVolume {
+    pub name: String,
+    pub driver: String,
+    pub mountpoint: String,
+    pub labels: Option<HashMap<String, String>>,
+    pub usage_data: Option<ApiVolumeUsage>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiVolumeList {
+    pub volumes: Option<Vec<ApiVolume>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiNetwork {
+    pub name: String,
+    pub id: String,
+    pub labels: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiBuildCache {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "Type")]
+    pub kind: String,
+    pub description: String,
+    pub in_use: bool,
+    pub shared: bool,
+    pub size: i64,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub usage_count: i64,
+}
+
+/// Response of `GET /system/df`.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct ApiDiskUsage {
+    pub layers_size: i64,
+    pub images: Option<Vec<ApiImage>>,
+    pub containers: Option<Vec<ApiContainer>>,
+    pub volumes: Option<Vec<ApiVolume>>,
+    pub build_cache: Option<Vec<ApiBuildCache>>,
+}
+
+impl DockerApi {
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        DockerApi {
+            socket: socket.into(),
+        }
+    }
+
+    /// Whether a daemon answers on the socket.
+    pub fn ping(&self) -> bool {
+        matches!(self.request("GET", "/_ping"), Ok((200, _)))
+    }
+
+    /// `GET`s `path` and decodes the JSON body.
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        let (status, body) = self.request("GET", path)?;
+        if !(200..300).contains(&status) {
+            return Err(api_error(path, status, &body));
+        }
+        serde_json::from_slice(&body).map_err(|e| format!("Docker API {path}: invalid JSON: {e}"))
+    }
+
+    /// Sends a bodyless request and returns the status code and body.
+    pub fn request(&self, method: &str, path: &str) -> Result<(u16, Vec<u8>), String> {
+        let mut stream = UnixStream::connect(&self.socket)
+            .map_err(|e| format!("Cannot connect to {}: {e}", self.socket.display()))?;
+        stream
+            .set_read_timeout(Some(REQUEST_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(REQUEST_TIMEOUT)))
+            .map_err(|e| format!("Docker API socket: {e}"))?;
+
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: docker\r\nUser-Agent: cleanos-ai\r\n\
+             Accept: application/json\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        );
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| format!("Docker API {path}: {e}"))?;
+
+        let mut raw = Vec::new();
+        stream
+            .read_to_end(&mut raw)
+            .map_err(|e| format!("Docker API {path}: {e}"))?;
+        parse_response(&raw).map_err(|e| format!("Docker API {path}: {e}"))
+    }
+}
+
+fn api_error(path: &str, status: u16, body: &[u8]) -> String {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        message: String,
+    }
+    let message = serde_json::from_slice::<ErrorBody>(body)
+        .map(|e| e.message)
+        .unwrap_or_else(|_| String::from_utf8_lossy(body).trim().to_string());
+    format!("Docker API {path} returned {status}: {message}")
+}
+
+/// Splits a raw HTTP/1.x response into its status code and decoded body.
+fn parse_response(raw: &[u8]) -> Result<(u16, Vec<u8>), String> {
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("incomplete response headers")?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let body = &raw[header_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or("malformed status line")?;
+
+    let mut chunked = false;
+    let mut content_length = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse::<usize>().ok();
+        }
+    }
+
+    let body = if chunked {
+        decode_chunked(body)?
+    } else if let Some(len) = content_length {
+        body.get(..len).ok_or("truncated response body")?.to_vec()
+    } else {
+        body.to_vec()
+    };
+    Ok((status, body))
+}
+
+/// Decodes a `Transfer-Encoding: chunked` body; trailers are ignored.
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or("truncated chunk header")?;
+        let size_line = String::from_utf8_lossy(&body[..line_end]);
+        let size_hex = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| format!("invalid chunk size {size_hex:?}"))?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(out);
+        }
+        let chunk = body.get(..size).ok_or("truncated chunk")?;
+        out.extend_from_slice(chunk);
+        body = body.get(size + 2..).ok_or("truncated chunk")?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Serves one canned `response` on a socket in a fresh temp directory and
+    /// returns the request line the client sent.
+    fn serve(name: &str, response: &'static [u8]) -> (DockerApi, JoinHandle<String>) {
+        let dir =
+            std::env::temp_dir().join(format!("cleanos-docker-api-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("docker.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                assert!(n > 0, "client closed before sending headers");
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response).unwrap();
+            drop(stream);
+            let _ = std::fs::remove_dir_all(&dir);
+            let request = String::from_utf8_lossy(&request).to_string();
+            request.lines().next().unwrap_or_default().to_string()
+        });
+        (DockerApi::new(socket), server)
+    }
+
+    #[test]
+    fn decodes_chunked_body() {
+        let (api, server) = serve(
+            "chunked",
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+              Transfer-Encoding: chunked\r\n\r\n\
+              6\r\n{\"Volu\r\n\
+              b;ext=1\r\nmes\":[],\"Wa\r\n\
+              d\r\nrnings\":null}\r\n\
+              0\r\n\r\n",
+        );
+        let list: ApiVolumeList = api.get("/volumes").unwrap();
+        assert_eq!(list.volumes.map(|v| v.len()), Some(0));
+        assert_eq!(server.join().unwrap(), "GET /volumes HTTP/1.1");
+    }
+
+    #[test]
+    fn reads_content_length_body() {
+        let (api, server) = serve(
+            "length",
+            b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOKtrailing bytes",
+        );
+        let (status, body) = api.request("GET", "/_ping").unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, b"OK");
+        assert_eq!(server.join().unwrap(), "GET /_ping HTTP/1.1");
+    }
+
+    #[test]
+    fn reports_error_status() {
+        let (api, server) = serve(
+            "error",
+            b"HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\
+              Content-Length: 35\r\n\r\n{\"message\":\"no such image: ghost\"}\n",
+        );
+        let err = api.get::<ApiImage>("/images/ghost/json").unwrap_err();
+        assert_eq!(
+            err,
+            "Docker API /images/ghost/json returned 404: no such image: ghost"
+        );
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+//! Docker Compose project grouping and teardown.
+//!
+//! Compose labels every container, volume and network it creates with
+//! `com.docker.compose.project`, and containers also with the project's
+//! working directory. Images carry no reliable project label, so a project's
+//! images are the ones its containers run. Teardown works from the labels
+//! alone, so it also handles projects whose compose file is gone and runtimes
+//! without a compose plugin.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::container_runtime::ContainerRuntime;
+use crate::docker::{self, ContainerRef, DockerInfo};
+use crate::system::CleanupResult;
+
+pub const PROJECT_LABEL: &str = "com.docker.compose.project";
+const WORKING_DIR_LABEL: &str = "com.docker.compose.project.working_dir";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComposeProject {
+    pub name: String,
+    /// Directory `docker compose up` ran in, when the containers record it.
+    pub working_dir: Option<String>,
+    /// Whether any of the project's containers is running.
+    pub running: bool,
+    /// Short container IDs.
+    pub containers: Vec<String>,
+    /// Short IDs of the images the project's containers run.
+    pub images: Vec<String>,
+    pub volumes: Vec<String>,
+    pub networks: Vec<String>,
+    /// Container, volume and image bytes. Images shared with other projects
+    /// are counted in each of them.
+    pub size: u64,
+}
+
+impl ComposeProject {
+    fn new(name: &str) -> Self {
+        ComposeProject {
+            name: name.to_string(),
+            working_dir: None,
+            running: false,
+            containers: Vec::new(),
+            images: Vec::new(),
+            volumes: Vec::new(),
+            networks: Vec::new(),
+            size: 0,
+        }
+    }
+}
+
+/// Groups `info` by compose project. `volume_projects` and
+/// `network_projects` map labeled volume and network names to their project.
+pub fn group_projects(
+    info: &DockerInfo,
+    refs: &[ContainerRef],
+    volume_projects: &HashMap<String, String>,
+    network_projects: &HashMap<String, String>,
+) -> Vec<ComposeProject> {
+    let mut projects: HashMap<String, ComposeProject> = HashMap::new();
+
+    for r in refs {
+        let Some(name) = r.labels.get(PROJECT_LABEL) else {
+            continue;
+        };
+        let project = projects
+            .entry(name.clone())
+            .or_insert_with(|| ComposeProject::new(name));
+        if project.working_dir.is_none() {
+            project.working_dir = r.labels.get(WORKING_DIR_LABEL).cloned();
+        }
+        project.running |= r.running;
+        project.containers.push(r.id.clone());
+        if !project.images.contains(&r.image_id) {
+            project.images.push(r.image_id.clone());
+        }
+        project.size += info
+            .containers
+            .iter()
+            .find(|c| c.id == r.id)
+            .map_or(0, |c| c.size);
+    }
+
+    for (volume, name) in volume_projects {
+        let project = projects
+            .entry(name.clone())
+            .or_insert_with(|| ComposeProject::new(name));
+        project.volumes.push(volume.clone());
+        project.size += info
+            .volumes
+            .iter()
+            .find(|v| &v.name == volume)
+            .map_or(0, |v| v.size);
+    }
+
+    for (network, name) in network_projects {
+        projects
+            .entry(name.clone())
+            .or_insert_with(|| ComposeProject::new(name))
+            .networks
+            .push(network.clone());
+    }
+
+    let mut projects: Vec<ComposeProject> = projects
+        .into_values()
+        .map(|mut project| {
+            // `images` has one row per tag; count each image once.
+            project.size += project
+                .images
+                .iter()
+                .filter_map(|id| info.images.iter().find(|i| &i.id == id))
+                .map(|i| i.size)
+                .sum::<u64>();
+            project.volumes.sort();
+            project.networks.sort();
+            project
+        })
+        .collect();
+    projects.sort_by_key(|p| std::cmp::Reverse(p.size));
+    projects
+}
+
+/// Removes every container, volume and network of compose project
+/// `project`, like `docker compose down --volumes`. Named volumes go with
+/// their data. Images are kept; they become prunable once no container uses
+/// them. A project with running containers is refused unless `force` is set.
+pub fn teardown_compose_project(
+    project: &str,
+    force: bool,
+    runtime: Option<&str>,
+) -> Result<CleanupResult, String> {
+    let info = docker::get_docker_info(runtime)?;
+    let Some(group) = info.compose_projects.iter().find(|p| p.name == project) else {
+        return Err(format!("No compose project named {project}"));
+    };
+    if group.running && !force {
+        return Err(format!(
+            "{project} has running containers. Stop it first, or force the \
+             teardown to remove the running stack and its volumes."
+        ));
+    }
+    let rt = ContainerRuntime::resolve(Some(&info.runtime))?;
+
+    let mut messages = Vec::new();
+    let mut errors = Vec::new();
+    let mut space_freed = 0;
+
+    // Containers first: volumes and networks in use cannot be removed.
+    let stages: [(&str, &[&str], &Vec<String>); 3] = [
+        ("containers", &["rm", "-f", "-v"], &group.containers),
+        ("volumes", &["volume", "rm"], &group.volumes),
+        ("networks", &["network", "rm"], &group.networks),
+    ];
+    for (what, args, names) in stages {
+        if names.is_empty() {
+            continue;
+        }
+        let output = rt
+            .command()
+            .args(args)
+            .args(names)
+            .output()
+            .map_err(|e| format!("{} {}: {e}", rt.name(), args.join(" ")))?;
+        if output.status.success() {
+            messages.push(match what {
+                "volumes" => format!("{} named volumes and their data", names.len()),
+                _ => format!("{} {what}", names.len()),
+            });
+            space_freed += match what {
+                "containers" => info
+                    .containers
+                    .iter()
+                    .filter(|c| names.contains(&c.id))
+                    .map(|c| c.size)
+                    .sum(),
+                "volumes" => info
+                    .volumes
+                    .iter()
+                    .filter(|v| names.contains(&v.name))
+                    .map(|v| v.size)
+                    .sum(),
+                _ => 0,
+            };
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            errors.push(format!("Failed to remove {what}: {}", stderr.trim()));
+        }
+    }
+
+    let mut message = if messages.is_empty() {
+        format!("Nothing removed from {project}")
+    } else {
+        format!("Removed {} from {project}", messages.join(", "))
+    };
+    if !errors.is_empty() {
+        message = format!("{message}; {}", errors.join("; "));
+    }
+    Ok(CleanupResult {
+        success: errors.is_empty(),
+        space_freed,
+        message,
+    })
+}
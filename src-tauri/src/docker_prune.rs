@@ -0,0 +1,454 @@
+//! Policy-based container pruning.
+//!
+//! `prune -a` style cleanups also delete base images a developer pulls every
+//! day. A [`PrunePolicy`] narrows a prune to old objects, to objects with (or
+//! without) given labels, keeps the newest tags of every repository and never
+//! touches images that a local compose file refers to.
+//!
+//! Containers, volumes and build cache go through the runtime's own `prune`
+//! filters. Images are selected here, because `image prune` cannot express
+//! per-repository retention or compose protection, and are removed tag by tag
+//! with a plain `rmi`, which refuses images that containers still use.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::artifacts::marked_artifact_kind;
+use crate::cleaners::expand_home;
+use crate::container_runtime::{ContainerRuntime, RuntimeKind};
+use crate::docker::{container_refs, parse_reclaimed_space, short_id};
+use crate::system::CleanupResult;
+
+const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// How deep to look for compose files below each search directory.
+const COMPOSE_SEARCH_DEPTH: usize = 4;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PrunePolicy {
+    /// Only prune objects created more than this many hours ago
+    /// (`--filter until=<N>h`). Volumes have no creation filter.
+    pub older_than_hours: Option<u64>,
+    /// Only prune objects carrying every one of these labels, given as `key`
+    /// or `key=value`.
+    pub include_labels: Vec<String>,
+    /// Never prune objects carrying any of these labels. Build cache has no
+    /// labels, so both label lists are ignored for it.
+    pub exclude_labels: Vec<String>,
+    /// Keep this many of the most recently created tags of every repository.
+    pub keep_recent_tags: Option<usize>,
+    /// Also prune unused named volumes, not only anonymous ones. Docker 23+
+    /// and nerdctl only prune anonymous volumes unless asked
+    /// (`--filter all=true`, `--all`); Podman always prunes every unused
+    /// volume, named or not, so this has no effect there.
+    pub include_named_volumes: bool,
+    /// Directories searched for compose files whose images are kept. The home
+    /// directory is searched when empty.
+    pub compose_dirs: Vec<String>,
+}
+
+impl PrunePolicy {
+    fn filter_args(&self, with_until: bool, with_labels: bool) -> Vec<String> {
+        let mut filters = Vec::new();
+        if let Some(hours) = self.older_than_hours.filter(|_| with_until) {
+            filters.push(format!("until={hours}h"));
+        }
+        if with_labels {
+            filters.extend(self.include_labels.iter().map(|l| format!("label={l}")));
+            filters.extend(self.exclude_labels.iter().map(|l| format!("label!={l}")));
+        }
+        filters
+            .into_iter()
+            .flat_map(|f| ["--filter".to_string(), f])
+            .collect()
+    }
+
+    fn matches_labels(&self, labels: &HashMap<String, String>) -> bool {
+        let has = |filter: &String| match filter.split_once('=') {
+            Some((key, value)) => labels.get(key).is_some_and(|v| v == value),
+            None => labels.contains_key(filter.as_str()),
+        };
+        self.include_labels.iter().all(has) && !self.exclude_labels.iter().any(has)
+    }
+}
+
+/// The subset of `<runtime> image inspect` output the image policy needs.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InspectedImage {
+    id: String,
+    repo_tags: Option<Vec<String>>,
+    created: String,
+    size: i64,
+    config: Option<InspectedConfig>,
+    /// Podman also reports labels at the top level.
+    labels: Option<HashMap<String, String>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase", default)]
+struct InspectedConfig {
+    labels: Option<HashMap<String, String>>,
+}
+
+impl InspectedImage {
+    fn labels(&self) -> HashMap<String, String> {
+        self.config
+            .as_ref()
+            .and_then(|c| c.labels.clone())
+            .or_else(|| self.labels.clone())
+            .unwrap_or_default()
+    }
+
+    fn created_at(&self) -> i64 {
+        chrono::DateTime::parse_from_rfc3339(&self.created)
+            .map(|d| d.timestamp())
+            .unwrap_or(0)
+    }
+}
+
+/// Prunes `target` (`images`, `containers`, `volumes`, `build-cache` or
+/// `all`) on `runtime` according to `policy`.
+pub fn prune_docker(
+    target: &str,
+    policy: &PrunePolicy,
+    runtime: Option<&str>,
+) -> Result<CleanupResult, String> {
+    let rt = ContainerRuntime::resolve(runtime)?;
+    let targets: &[&str] = match target {
+        "images" | "containers" | "volumes" | "build-cache" => &[target],
+        // Containers first, so the images they held become prunable.
+        "all" => &["containers", "images", "volumes", "build-cache"],
+        _ => return Err(format!("Unknown docker cleanup target: {target}")),
+    };
+
+    let mut space_freed = 0;
+    let mut messages = Vec::new();
+    let mut success = true;
+    for target in targets {
+        let result = if *target == "images" {
+            prune_images(&rt, policy)
+        } else {
+            prune_with_filters(&rt, target, policy)
+        };
+        match result {
+            Ok(r) => {
+                space_freed += r.space_freed;
+                success &= r.success;
+                if !r.message.is_empty() {
+                    messages.push(r.message);
+                }
+            }
+            Err(e) => {
+                success = false;
+                messages.push(e);
+            }
+        }
+    }
+
+    Ok(CleanupResult {
+        success,
+        space_freed,
+        message: messages.join("\n"),
+    })
+}
+
+fn prune_with_filters(
+    rt: &ContainerRuntime,
+    target: &str,
+    policy: &PrunePolicy,
+) -> Result<CleanupResult, String> {
+    let (mut args, filters): (Vec<&str>, Vec<String>) = match target {
+        "containers" => (
+            vec!["container", "prune", "-f"],
+            policy.filter_args(true, true),
+        ),
+        "volumes" => {
+            let mut args = vec!["volume", "prune", "-f"];
+            let mut filters = policy.filter_args(false, true);
+            if policy.include_named_volumes {
+                match rt.kind {
+                    RuntimeKind::Docker => {
+                        filters.extend(["--filter".to_string(), "all=true".to_string()])
+                    }
+                    RuntimeKind::Nerdctl => args.push("--all"),
+                    RuntimeKind::Podman => {}
+                }
+            }
+            (args, filters)
+        }
+        "build-cache" if rt.kind == RuntimeKind::Podman => (
+            vec!["image", "prune", "--build-cache", "-f"],
+            policy.filter_args(true, false),
+        ),
+        "build-cache" => (
+            vec!["builder", "prune", "-a", "-f"],
+            policy.filter_args(true, false),
+        ),
+        _ => return Err(format!("Unknown docker cleanup target: {target}")),
+    };
+    args.extend(filters.iter().map(String::as_str));
+
+    let output = rt
+        .command()
+        .args(&args)
+        .output()
+        .map_err(|e| format!("{} {target} prune: {e}", rt.name()))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "{} {target} prune failed: {}",
+            rt.name(),
+            stderr.trim()
+        ));
+    }
+    Ok(CleanupResult {
+        success: true,
+        space_freed: parse_reclaimed_space(&stdout),
+        message: stdout.trim().to_string(),
+    })
+}
+
+/// Removes the image tags `policy` selects. Space is counted for images
+/// whose last reference was removed; layers shared with kept images make
+/// this an upper bound.
+fn prune_images(rt: &ContainerRuntime, policy: &PrunePolicy) -> Result<CleanupResult, String> {
+    let images = inspect_images(rt)?;
+    let in_use: HashSet<String> = container_refs(rt)?
+        .into_iter()
+        .map(|r| r.image_id)
+        .collect();
+    let protected = compose_references(policy);
+    let cutoff = policy
+        .older_than_hours
+        .map(|h| chrono::Utc::now().timestamp() - (h as i64) * 3_600);
+
+    // Newest first, so retention keeps the most recent tags.
+    let mut tagged: Vec<(&InspectedImage, String)> = images
+        .iter()
+        .flat_map(|image| {
+            image
+                .repo_tags
+                .iter()
+                .flatten()
+                .filter(|t| *t != "<none>:<none>")
+                .map(move |t| (image, t.clone()))
+        })
+        .collect();
+    tagged.sort_by_key(|(image, _)| std::cmp::Reverse(image.created_at()));
+
+    let mut seen_per_repo: HashMap<String, usize> = HashMap::new();
+    let mut candidates: Vec<(&InspectedImage, String)> = Vec::new();
+    for (image, tag) in tagged {
+        let (repo, _) = normalize_reference(&tag);
+        let seen = seen_per_repo.entry(repo).or_default();
+        *seen += 1;
+        if policy.keep_recent_tags.is_some_and(|keep| *seen <= keep) {
+            continue;
+        }
+        if is_protected(&tag, &protected) {
+            continue;
+        }
+        candidates.push((image, tag));
+    }
+    // Untagged (dangling) images are removed by ID.
+    candidates.extend(
+        images
+            .iter()
+            .filter(|i| {
+                i.repo_tags
+                    .as_ref()
+                    .is_none_or(|t| t.iter().all(|t| t == "<none>:<none>"))
+            })
+            .map(|i| (i, i.id.clone())),
+    );
+    candidates.retain(|(image, _)| {
+        !in_use.contains(&short_id(&image.id))
+            && cutoff.is_none_or(|c| image.created_at() < c)
+            && policy.matches_labels(&image.labels())
+    });
+
+    let total = candidates.len();
+    let mut removed_refs: HashMap<&str, usize> = HashMap::new();
+    let mut removed = 0;
+    let mut errors = Vec::new();
+    for (image, reference) in &candidates {
+        let output = rt
+            .command()
+            .args(["rmi", reference])
+            .output()
+            .map_err(|e| format!("{} rmi: {e}", rt.name()))?;
+        if output.status.success() {
+            removed += 1;
+            *removed_refs.entry(image.id.as_str()).or_default() += 1;
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            errors.push(format!("Failed to remove {reference}: {}", stderr.trim()));
+        }
+    }
+
+    let space_freed = images
+        .iter()
+        .filter(|image| {
+            let refs = image.repo_tags.as_ref().map_or(0, |t| t.len()).max(1);
+            removed_refs
+                .get(image.id.as_str())
+                .is_some_and(|&n| n >= refs)
+        })
+        .map(|image| image.size.max(0) as u64)
+        .sum();
+
+    let mut message = format!("Removed {removed} of {total} images");
+    if !errors.is_empty() {
+        message = format!("{message}; {}", errors.join("; "));
+    }
+    Ok(CleanupResult {
+        success: errors.is_empty(),
+        space_freed,
+        message,
+    })
+}
+
+fn inspect_images(rt: &ContainerRuntime) -> Result<Vec<InspectedImage>, String> {
+    let output = rt
+        .command()
+        .args(["images", "-q", "--no-trunc"])
+        .output()
+        .map_err(|e| format!("{} images: {e}", rt.name()))?;
+    let mut ids: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let output = rt
+        .command()
+        .args(["image", "inspect"])
+        .args(&ids)
+        .output()
+        .map_err(|e| format!("{} image inspect: {e}", rt.name()))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} image inspect failed: {}",
+            rt.name(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("{} image inspect: invalid JSON: {e}", rt.name()))
+}
+
+/// Splits an image reference into a canonical repository and tag, so that
+/// `nginx`, `docker.io/library/nginx:latest` and `nginx:latest` compare
+/// equal. Digests are dropped.
+fn normalize_reference(reference: &str) -> (String, String) {
+    let reference = reference.split('@').next().unwrap_or(reference);
+    // The tag follows the first `:` of the last path component; earlier
+    // colons belong to a registry port.
+    let name_start = reference.rfind('/').map_or(0, |i| i + 1);
+    let (repo, tag) = match reference[name_start..].find(':') {
+        Some(i) => (
+            &reference[..name_start + i],
+            &reference[name_start + i + 1..],
+        ),
+        None => (reference, "latest"),
+    };
+    let repo = repo
+        .strip_prefix("docker.io/")
+        .or_else(|| repo.strip_prefix("index.docker.io/"))
+        .unwrap_or(repo);
+    let repo = repo.strip_prefix("library/").unwrap_or(repo);
+    (repo.to_string(), tag.to_string())
+}
+
+/// Whether `tag` is named by a compose file. A reference whose tag is
+/// interpolated (`app:${TAG}`) protects every tag of its repository.
+fn is_protected(tag: &str, protected: &HashSet<(String, String)>) -> bool {
+    let (repo, tag) = normalize_reference(tag);
+    protected.contains(&(repo.clone(), tag)) || protected.contains(&(repo, "*".to_string()))
+}
+
+/// `(repository, tag)` pairs of every `image:` in the compose files below
+/// the policy's search directories.
+fn compose_references(policy: &PrunePolicy) -> HashSet<(String, String)> {
+    let roots: Vec<PathBuf> = if policy.compose_dirs.is_empty() {
+        dirs::home_dir().into_iter().collect()
+    } else {
+        policy.compose_dirs.iter().map(|d| expand_home(d)).collect()
+    };
+
+    let mut references = HashSet::new();
+    for root in roots {
+        for file in compose_files(&root) {
+            let Ok(content) = fs::read_to_string(&file) else {
+                continue;
+            };
+            references.extend(parse_compose_images(&content));
+        }
+    }
+    references
+}
+
+fn compose_files(root: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut walker = WalkDir::new(root)
+        .max_depth(COMPOSE_SEARCH_DEPTH)
+        .follow_links(false)
+        .into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else { continue };
+        let name = entry.file_name().to_string_lossy();
+        if entry.file_type().is_dir() {
+            if entry.depth() > 0
+                && (name.starts_with('.') || marked_artifact_kind(entry.path()).is_some())
+            {
+                walker.skip_current_dir();
+            }
+        } else if COMPOSE_FILES.contains(&name.as_ref()) {
+            found.push(entry.into_path());
+        }
+    }
+    found
+}
+
+/// Reads the `image:` keys of a compose file. Compose files are YAML, but
+/// image references are always plain scalars on a single line, so a line
+/// scan is enough.
+fn parse_compose_images(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start().trim_start_matches("- ");
+            let value = line.strip_prefix("image:")?;
+            let value = value.split(" #").next().unwrap_or(value);
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            if value.is_empty() {
+                return None;
+            }
+            let (repo, tag) = normalize_reference(value);
+            if repo.contains('$') {
+                None
+            } else if tag.contains('$') {
+                Some((repo, "*".to_string()))
+            } else {
+                Some((repo, tag))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,303 @@
+//! Container disk usage per category and build cache records.
+//!
+//! Reclaimable figures follow the Docker CLI's `system df`: image space in
+//! use is the unshared part of images with containers, container space is
+//! the writable layer of containers that are not running, volume space is
+//! volumes no container references, and build cache space is everything not
+//! in use. Shared build cache records are excluded from both totals because
+//! their bytes are also counted by the records that share them.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::container_runtime::{ContainerRuntime, RuntimeKind};
+use crate::docker::{self, ContainerRef, DockerInfo};
+use crate::docker_api::{ApiBuildCache, ApiContainer, ApiDiskUsage};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageCategory {
+    Images,
+    Containers,
+    Volumes,
+    BuildCache,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiskUsageCategory {
+    pub category: UsageCategory,
+    pub total_count: u64,
+    pub active_count: u64,
+    pub size: u64,
+    pub reclaimable: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BuildCacheRecord {
+    pub id: String,
+    /// BuildKit record type: `regular`, `source.local`, `exec.cachemount`,
+    /// `frontend` or `internal`.
+    pub kind: String,
+    pub description: String,
+    pub size: u64,
+    /// Shared records hold layers that other records also use.
+    pub shared: bool,
+    pub in_use: bool,
+    /// Relative time like "3 days ago"; `None` for never-used records.
+    pub last_used: Option<String>,
+    pub usage_count: u64,
+}
+
+/// Categories and build cache records from the API's `/system/df`.
+pub fn from_api(df: &ApiDiskUsage) -> (Vec<DiskUsageCategory>, Vec<BuildCacheRecord>) {
+    let images = df.images.as_deref().unwrap_or_default();
+    let used_image_bytes: i64 = images
+        .iter()
+        .filter(|i| i.containers > 0 && i.size >= 0 && i.shared_size >= 0)
+        .map(|i| i.size - i.shared_size)
+        .sum();
+
+    let containers = df.containers.as_deref().unwrap_or_default();
+    let running = |state: &str| matches!(state, "running" | "paused" | "restarting");
+    let container_size = |c: &ApiContainer| c.size_rw.unwrap_or(0).max(0) as u64;
+
+    let volumes = df.volumes.as_deref().unwrap_or_default();
+    let volume_size = |size: i64| size.max(0) as u64;
+
+    let records: Vec<BuildCacheRecord> = df
+        .build_cache
+        .iter()
+        .flatten()
+        .map(record_from_api)
+        .collect();
+
+    let categories = vec![
+        DiskUsageCategory {
+            category: UsageCategory::Images,
+            total_count: images.len() as u64,
+            active_count: images.iter().filter(|i| i.containers > 0).count() as u64,
+            size: df.layers_size.max(0) as u64,
+            reclaimable: (df.layers_size - used_image_bytes).max(0) as u64,
+        },
+        DiskUsageCategory {
+            category: UsageCategory::Containers,
+            total_count: containers.len() as u64,
+            active_count: containers.iter().filter(|c| running(&c.state)).count() as u64,
+            size: containers.iter().map(container_size).sum(),
+            reclaimable: containers
+                .iter()
+                .filter(|c| !running(&c.state))
+                .map(container_size)
+                .sum(),
+        },
+        DiskUsageCategory {
+            category: UsageCategory::Volumes,
+            total_count: volumes.len() as u64,
+            active_count: volumes
+                .iter()
+                .filter(|v| v.usage_data.as_ref().is_some_and(|u| u.ref_count > 0))
+                .count() as u64,
+            size: volumes
+                .iter()
+                .filter_map(|v| v.usage_data.as_ref())
+                .map(|u| volume_size(u.size))
+                .sum(),
+            reclaimable: volumes
+                .iter()
+                .filter_map(|v| v.usage_data.as_ref())
+                .filter(|u| u.ref_count == 0)
+                .map(|u| volume_size(u.size))
+                .sum(),
+        },
+        build_cache_category(&records),
+    ];
+    (categories, records)
+}
+
+fn record_from_api(record: &ApiBuildCache) -> BuildCacheRecord {
+    let last_used = record
+        .last_used_at
+        .as_deref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| docker::created_since(t.timestamp()));
+    BuildCacheRecord {
+        id: record.id.clone(),
+        kind: record.kind.clone(),
+        description: record.description.clone(),
+        size: record.size.max(0) as u64,
+        shared: record.shared,
+        in_use: record.in_use,
+        last_used,
+        usage_count: record.usage_count.max(0) as u64,
+    }
+}
+
+fn build_cache_category(records: &[BuildCacheRecord]) -> DiskUsageCategory {
+    let private = || records.iter().filter(|r| !r.shared);
+    DiskUsageCategory {
+        category: UsageCategory::BuildCache,
+        total_count: records.len() as u64,
+        active_count: records.iter().filter(|r| r.in_use).count() as u64,
+        size: private().map(|r| r.size).sum(),
+        reclaimable: private().filter(|r| !r.in_use).map(|r| r.size).sum(),
+    }
+}
+
+/// Categories from `<runtime> system df`, or `None` when the runtime has no
+/// such command (nerdctl) or its output cannot be read.
+pub fn from_cli(rt: &ContainerRuntime) -> Option<Vec<DiskUsageCategory>> {
+    if rt.kind == RuntimeKind::Nerdctl {
+        return None;
+    }
+    let output = rt
+        .command()
+        .args(["system", "df", "--format", "{{json .}}"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())?;
+    let categories: Vec<DiskUsageCategory> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|row| parse_df_row(&row))
+        .collect();
+    (!categories.is_empty()).then_some(categories)
+}
+
+/// One `system df` row. Docker reports counts and sizes as strings such as
+/// "12" and "1.2GB (40%)"; Podman uses `Total` instead of `TotalCount` and
+/// may report raw byte counts.
+fn parse_df_row(row: &Value) -> Option<DiskUsageCategory> {
+    let category = match row.get("Type")?.as_str()? {
+        "Images" => UsageCategory::Images,
+        "Containers" => UsageCategory::Containers,
+        "Local Volumes" => UsageCategory::Volumes,
+        "Build Cache" => UsageCategory::BuildCache,
+        _ => return None,
+    };
+    let count = |key: &str| match row.get(key) {
+        Some(Value::Number(n)) => n.as_u64(),
+        Some(Value::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    };
+    let bytes = |raw: &str, key: &str| match (row.get(raw), row.get(key)) {
+        (Some(Value::Number(n)), _) | (_, Some(Value::Number(n))) => n.as_u64().unwrap_or(0),
+        (_, Some(Value::String(s))) => docker::parse_size_string(s),
+        _ => 0,
+    };
+    Some(DiskUsageCategory {
+        category,
+        total_count: count("TotalCount").or_else(|| count("Total")).unwrap_or(0),
+        active_count: count("Active").unwrap_or(0),
+        size: bytes("RawSize", "Size"),
+        reclaimable: bytes("RawReclaimable", "Reclaimable"),
+    })
+}
+
+/// Build cache records from `docker buildx du --verbose`. Empty for other
+/// runtimes or when buildx is not installed.
+pub fn buildx_records(rt: &ContainerRuntime) -> Vec<BuildCacheRecord> {
+    if rt.kind != RuntimeKind::Docker {
+        return Vec::new();
+    }
+    match rt.command().args(["buildx", "du", "--verbose"]).output() {
+        Ok(out) if out.status.success() => parse_buildx_du(&String::from_utf8_lossy(&out.stdout)),
+        Ok(out) => {
+            log::debug!(
+                "docker buildx du failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+            Vec::new()
+        }
+        Err(e) => {
+            log::debug!("docker buildx du: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Parses the blank-line separated `Key: value` blocks of
+/// `buildx du --verbose`. The trailing totals block has no `ID` and is
+/// skipped.
+fn parse_buildx_du(output: &str) -> Vec<BuildCacheRecord> {
+    output
+        .split("\n\n")
+        .filter_map(|block| {
+            let fields: HashMap<&str, &str> = block
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect();
+            let flag = |key: &str| fields.get(key).is_some_and(|v| *v == "true");
+            Some(BuildCacheRecord {
+                id: fields.get("ID")?.trim_end_matches('*').to_string(),
+                kind: fields.get("Type").unwrap_or(&"").to_string(),
+                description: fields.get("Description").unwrap_or(&"").to_string(),
+                size: fields
+                    .get("Size")
+                    .map_or(0, |s| docker::parse_size_string(s)),
+                shared: flag("Shared"),
+                // buildx reports whether a record can be pruned, i.e. is not in use.
+                in_use: fields.get("Reclaimable").is_some_and(|v| *v == "false"),
+                last_used: fields
+                    .get("Last used")
+                    .filter(|v| !v.is_empty())
+                    .map(|v| v.to_string()),
+                usage_count: fields
+                    .get("Usage count")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+/// Categories computed from the listed objects, for runtimes without
+/// `system df`. Image sizes include shared layers, so the image figures are
+/// upper bounds.
+pub fn estimate(info: &DockerInfo, refs: &[ContainerRef]) -> Vec<DiskUsageCategory> {
+    let mut images: HashMap<&str, (u64, bool)> = HashMap::new();
+    for image in &info.images {
+        images.insert(&image.id, (image.size, image.in_use));
+    }
+    let running = |id: &str| refs.iter().any(|r| r.id == id && r.running);
+
+    vec![
+        DiskUsageCategory {
+            category: UsageCategory::Images,
+            total_count: images.len() as u64,
+            active_count: images.values().filter(|(_, used)| *used).count() as u64,
+            size: images.values().map(|(size, _)| size).sum(),
+            reclaimable: images
+                .values()
+                .filter(|(_, used)| !used)
+                .map(|(size, _)| size)
+                .sum(),
+        },
+        DiskUsageCategory {
+            category: UsageCategory::Containers,
+            total_count: info.containers.len() as u64,
+            active_count: info.containers.iter().filter(|c| running(&c.id)).count() as u64,
+            size: info.containers.iter().map(|c| c.size).sum(),
+            reclaimable: info
+                .containers
+                .iter()
+                .filter(|c| !running(&c.id))
+                .map(|c| c.size)
+                .sum(),
+        },
+        DiskUsageCategory {
+            category: UsageCategory::Volumes,
+            total_count: info.volumes.len() as u64,
+            active_count: info.volumes.iter().filter(|v| v.in_use).count() as u64,
+            size: info.volumes.iter().map(|v| v.size).sum(),
+            reclaimable: info
+                .volumes
+                .iter()
+                .filter(|v| !v.in_use)
+                .map(|v| v.size)
+                .sum(),
+        },
+    ]
+}
@@ -29,17 +29,38 @@ pub fn scan_directory(path: &str) -> Result<Vec<FileInfo>, String> {
         return Err(format!("Not a directory: {path}"));
     }
 
-    let entries: Vec<_> = WalkDir::new(root)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .collect();
+    let mut entries = Vec::new();
+    walk_dir(root, |entry| {
+        entries.push(entry.clone());
+        true
+    });
 
     let files: Vec<FileInfo> = entries.par_iter().filter_map(entry_to_file_info).collect();
 
     Ok(files)
 }
 
+/// Walks `root` without following links, passing every readable entry to
+/// `visit`; a directory for which `visit` returns `false` is not descended
+/// into. Returns whether every entry could be read.
+pub fn walk_dir<F>(root: &Path, mut visit: F) -> bool
+where
+    F: FnMut(&walkdir::DirEntry) -> bool,
+{
+    let mut complete = true;
+    let mut walker = WalkDir::new(root).follow_links(false).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            complete = false;
+            continue;
+        };
+        if !visit(&entry) && entry.file_type().is_dir() {
+            walker.skip_current_dir();
+        }
+    }
+    complete
+}
+
 fn entry_to_file_info(entry: &walkdir::DirEntry) -> Option<FileInfo> {
     let path = entry.path();
     let metadata = entry.metadata().ok()?;
@@ -116,7 +137,7 @@ fn path_ext(p: &Path) -> Option<String> {
     p.extension().map(|e| e.to_string_lossy().to_string())
 }
 
-fn modified_timestamp(meta: &fs::Metadata) -> i64 {
+pub fn modified_timestamp(meta: &fs::Metadata) -> i64 {
     meta.modified()
         .ok()
         .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
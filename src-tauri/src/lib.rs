@@ -2,26 +2,45 @@
 mod ai_client;
 #[allow(dead_code)]
 mod ai_prompts;
+mod ai_providers;
+mod artifacts;
+mod browsers;
+mod cache_audit;
 #[allow(dead_code)]
 mod caches;
+mod candidates;
+mod cargo_cache;
+mod classifier;
+mod cleaners;
 mod cleanup_commands;
 mod commands;
+mod container_runtime;
 #[allow(dead_code)]
 mod credentials;
 #[allow(dead_code)]
 mod database;
 #[allow(dead_code)]
 mod docker;
+mod docker_api;
+mod docker_compose;
+mod docker_prune;
+mod docker_usage;
 #[allow(dead_code)]
 mod filesystem;
 mod models;
+mod orphans;
+mod path_safety;
+mod recommendations;
+mod sandboxed_apps;
 #[allow(dead_code)]
 mod system;
+mod temp_files;
+mod trash;
 mod updater;
 
 use commands::AppState;
 use database::Database;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -42,7 +61,12 @@ pub fn run() {
         }
     };
 
-    let app_state = AppState { db: Arc::new(db) };
+    path_safety::load_settings(&db);
+
+    let app_state = AppState {
+        db: Arc::new(db),
+        recommendations: Mutex::new(Vec::new()),
+    };
 
     if let Err(e) = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -54,25 +78,61 @@ pub fn run() {
             commands::find_duplicates,
             commands::get_system_info,
             commands::get_storage_breakdown,
+            commands::get_container_runtimes,
             commands::get_docker_info,
             commands::clean_docker,
+            commands::prune_docker,
+            commands::teardown_compose_project,
             commands::get_package_caches,
             commands::clean_package_cache,
+            commands::preview_package_cache_cleanup,
             commands::fetch_available_models,
+            commands::list_provider_models,
+            commands::get_provider_endpoint,
+            commands::set_provider_endpoint,
             commands::chat_with_ai,
             commands::test_ai_connection,
             cleanup_commands::get_log_info,
             cleanup_commands::clean_logs,
+            cleanup_commands::get_temp_usage,
+            cleanup_commands::clean_temp_files,
+            cleanup_commands::get_trash_contents,
+            cleanup_commands::empty_trash,
+            cleanup_commands::restore_trash_item,
             cleanup_commands::get_browser_caches,
             cleanup_commands::clean_browser_cache,
+            cleanup_commands::get_browser_data_categories,
+            cleanup_commands::get_running_browsers,
+            cleanup_commands::cancel_queued_browser_cleanup,
+            cleanup_commands::get_browser_profiles,
+            cleanup_commands::clean_browser_profile,
+            cleanup_commands::audit_cache_dir,
+            cleanup_commands::clean_cache_entries,
+            cleanup_commands::clean_stale_cache_entries,
+            cleanup_commands::get_sandboxed_apps,
+            cleanup_commands::remove_unused_flatpak_runtimes,
+            cleanup_commands::remove_disabled_snap_revisions,
+            cleanup_commands::clean_orphaned_app_data,
+            cleanup_commands::find_orphans,
+            cleanup_commands::get_cargo_cache_summary,
+            cleanup_commands::find_cargo_target_dirs,
+            cleanup_commands::clean_cargo_target_dirs,
+            cleanup_commands::prune_cargo_registry,
+            cleanup_commands::clean_cargo_git_checkouts,
+            cleanup_commands::find_project_artifacts,
+            cleanup_commands::clean_project_artifacts,
+            cleanup_commands::sweep_inactive_projects,
             commands::analyze_files_with_ai,
+            commands::classify_files,
             commands::get_cleanup_recommendations,
+            commands::apply_recommendation,
             commands::store_api_key,
             commands::get_api_key,
             commands::delete_api_key,
             commands::has_api_key,
             commands::get_setting,
             commands::set_setting,
+            commands::get_path_policy,
             updater::check_for_updates,
             updater::install_update,
             updater::get_current_version,
@@ -1,3 +1,6 @@
+use crate::ai_client::{authorized, handle_response};
+use crate::ai_providers::{Connection, Protocol};
+
 /// Fetches available AI models from the OpenRouter public API.
 ///
 /// Returns the raw JSON response as a string for the frontend to parse.
@@ -23,3 +26,36 @@ pub async fn fetch_openrouter_models() -> Result<String, String> {
         .await
         .map_err(|e| format!("Failed to read response body: {e}"))
 }
+
+/// Lists the models a provider's server offers: `GET {base}/api/tags` for
+/// Ollama and `GET {base}/models` for OpenAI-compatible servers. Gemini and
+/// Claude models come from OpenRouter instead.
+pub async fn list_provider_models(connection: &Connection) -> Result<Vec<String>, String> {
+    let base = &connection.base_url;
+    let (url, list, field) = match connection.provider.protocol {
+        Protocol::Ollama => (format!("{base}/api/tags"), "models", "name"),
+        Protocol::OpenAi => (format!("{base}/models"), "data", "id"),
+        Protocol::Gemini | Protocol::Claude => {
+            return Err(format!(
+                "Model listing is not supported for {}",
+                connection.provider.id
+            ))
+        }
+    };
+
+    let client = reqwest::Client::new();
+    let response = authorized(client.get(&url), connection.api_key.as_deref())
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {e}"))?;
+    let json = handle_response(response).await?;
+    let mut models: Vec<String> = json[list]
+        .as_array()
+        .ok_or_else(|| format!("Unexpected response from {url}"))?
+        .iter()
+        .filter_map(|m| m[field].as_str().map(str::to_string))
+        .collect();
+    models.sort();
+    Ok(models)
+}
@@ -0,0 +1,410 @@
+//! Orphaned package and leftover config detection.
+//!
+//! Orphaned packages are dependencies nothing installed needs any more, as
+//! reported by the system package manager itself. Leftover directories are
+//! entries in `~/.config` and `~/.local/share` whose name matches no
+//! installed package, desktop entry or executable. Name matching is fuzzy by
+//! nature, so each leftover carries a confidence and the near matches that
+//! made it ambiguous, for the user or the AI to judge.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cleaners::command_exists;
+use crate::system::dir_size_and_count;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanPackage {
+    pub name: String,
+    /// Installed size in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeftoverConfidence {
+    /// Nothing installed resembles the directory name.
+    Likely,
+    /// Some installed name is similar; see `similar_to`.
+    Ambiguous,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeftoverDir {
+    pub name: String,
+    pub path: String,
+    /// `config` or `data`.
+    pub location: String,
+    pub size: u64,
+    pub confidence: LeftoverConfidence,
+    /// Installed package, desktop entry or executable names that partly match.
+    pub similar_to: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanReport {
+    /// `dnf`, `apt` or `pacman`; `None` when no supported manager was found.
+    pub package_manager: Option<String>,
+    pub packages: Vec<OrphanPackage>,
+    pub leftovers: Vec<LeftoverDir>,
+}
+
+/// Entries of `~/.config` and `~/.local/share` that belong to the desktop or
+/// to shared infrastructure rather than to one application.
+const SHARED_ENTRIES: &[&str] = &[
+    "applications",
+    "autostart",
+    "backgrounds",
+    "dconf",
+    "desktop-directories",
+    "enchant",
+    "environment.d",
+    "flatpak",
+    "fontconfig",
+    "fonts",
+    "gnome-session",
+    "gnome-shell",
+    "gtk-2.0",
+    "gtk-3.0",
+    "gtk-4.0",
+    "gvfs-metadata",
+    "ibus",
+    "icc",
+    "icons",
+    "keyrings",
+    "kwalletd",
+    "menus",
+    "mime",
+    "nautilus",
+    "plasma-workspace",
+    "pulse",
+    "recently-used.xbel",
+    "sounds",
+    "systemd",
+    "themes",
+    "Trash",
+    "user-places.xbel",
+    "xdg-desktop-portal",
+];
+
+/// Shortest name considered for a partial match; shorter names match far
+/// too much by accident.
+const MIN_PARTIAL_MATCH: usize = 4;
+
+pub fn find_orphans() -> Result<OrphanReport, String> {
+    let manager = detect_manager();
+    Ok(OrphanReport {
+        package_manager: manager.map(String::from),
+        packages: match manager {
+            Some(manager) => orphan_packages(manager)?,
+            None => Vec::new(),
+        },
+        leftovers: leftover_dirs(manager),
+    })
+}
+
+fn detect_manager() -> Option<&'static str> {
+    ["dnf", "apt-get", "pacman"]
+        .into_iter()
+        .find(|m| command_exists(m))
+        .map(|m| if m == "apt-get" { "apt" } else { m })
+}
+
+/// Runs a query command in the C locale, so sizes and labels parse the same
+/// everywhere.
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| format!("Failed to run {program}: {e}"))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn orphan_packages(manager: &str) -> Result<Vec<OrphanPackage>, String> {
+    let mut names: Vec<String> = match manager {
+        "dnf" => run("dnf", &["repoquery", "--unneeded", "--qf", "%{name}\n"])?
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect(),
+        // "Remv name [version]" per package that autoremove would remove.
+        "apt" => run("apt-get", &["-s", "autoremove"])?
+            .lines()
+            .filter_map(|l| l.strip_prefix("Remv "))
+            .filter_map(|l| l.split_whitespace().next())
+            .map(String::from)
+            .collect(),
+        "pacman" => run("pacman", &["-Qdtq"])?
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect(),
+        _ => return Err(format!("Unsupported package manager: {manager}")),
+    };
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sizes = installed_sizes(manager, &names);
+    let mut packages: Vec<OrphanPackage> = names
+        .into_iter()
+        .map(|name| OrphanPackage {
+            size: sizes
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, s)| *s)
+                .unwrap_or(0),
+            name,
+        })
+        .collect();
+    packages.sort_by_key(|p| std::cmp::Reverse(p.size));
+    Ok(packages)
+}
+
+/// Installed size in bytes of each named package.
+fn installed_sizes(manager: &str, names: &[String]) -> Vec<(String, u64)> {
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    match manager {
+        "dnf" => {
+            let mut args = vec!["-q", "--qf", "%{NAME} %{SIZE}\n"];
+            args.extend(&names);
+            parse_name_size(&run("rpm", &args).unwrap_or_default(), 1)
+        }
+        // Installed-Size is in KiB.
+        "apt" => {
+            let mut args = vec!["-W", "-f", "${Package} ${Installed-Size}\n"];
+            args.extend(&names);
+            parse_name_size(&run("dpkg-query", &args).unwrap_or_default(), 1024)
+        }
+        "pacman" => {
+            let mut args = vec!["-Qi"];
+            args.extend(&names);
+            parse_pacman_info(&run("pacman", &args).unwrap_or_default())
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn parse_name_size(output: &str, unit: u64) -> Vec<(String, u64)> {
+    output
+        .lines()
+        .filter_map(|l| {
+            let (name, size) = l.trim().split_once(' ')?;
+            Some((name.to_string(), size.trim().parse::<u64>().ok()? * unit))
+        })
+        .collect()
+}
+
+/// `Name` and `Installed Size` (e.g. `12.34 MiB`) of each `pacman -Qi` block.
+fn parse_pacman_info(output: &str) -> Vec<(String, u64)> {
+    let mut sizes = Vec::new();
+    let mut name = None;
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Installed Size" => {
+                if let Some(name) = name.take() {
+                    sizes.push((name, parse_binary_size(value.trim())));
+                }
+            }
+            _ => {}
+        }
+    }
+    sizes
+}
+
+fn parse_binary_size(s: &str) -> u64 {
+    let (num, unit) = s.split_once(' ').unwrap_or((s, "B"));
+    let num: f64 = num.parse().unwrap_or(0.0);
+    let multiplier = match unit {
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => 1.0,
+    };
+    (num * multiplier) as u64
+}
+
+// --- Leftover directories ---
+
+fn leftover_dirs(manager: Option<&str>) -> Vec<LeftoverDir> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let config = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+    let data = dirs::data_dir().unwrap_or_else(|| home.join(".local/share"));
+    let known = installed_names(manager);
+
+    let mut candidates: Vec<(PathBuf, &str, LeftoverConfidence, Vec<String>)> = Vec::new();
+    for (root, location) in [(config, "config"), (data, "data")] {
+        let Ok(entries) = fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !path.is_dir() || path.is_symlink() || SHARED_ENTRIES.contains(&name.as_str()) {
+                continue;
+            }
+            let key = normalize(&name);
+            if key.is_empty() || known.contains_key(&key) {
+                continue;
+            }
+            let similar = similar_names(&key, &known);
+            let confidence = if similar.is_empty() {
+                LeftoverConfidence::Likely
+            } else {
+                LeftoverConfidence::Ambiguous
+            };
+            candidates.push((path, location, confidence, similar));
+        }
+    }
+
+    let mut leftovers: Vec<LeftoverDir> = candidates
+        .into_par_iter()
+        .map(|(path, location, confidence, similar_to)| LeftoverDir {
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            size: dir_size_and_count(&path).0,
+            path: path.to_string_lossy().to_string(),
+            location: location.to_string(),
+            confidence,
+            similar_to,
+        })
+        .collect();
+    leftovers.sort_by_key(|l| std::cmp::Reverse(l.size));
+    leftovers
+}
+
+/// Lowercase alphanumerics only, so `Google-Chrome` and `google_chrome` agree.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Up to five known names whose normalized form contains, or is contained
+/// in, `key`, closest in length first.
+fn similar_names(key: &str, known: &HashMap<String, String>) -> Vec<String> {
+    if key.len() < MIN_PARTIAL_MATCH {
+        return Vec::new();
+    }
+    let mut similar: Vec<(&String, &String)> = known
+        .iter()
+        .filter(|(k, _)| {
+            k.len() >= MIN_PARTIAL_MATCH && (k.contains(key) || key.contains(k.as_str()))
+        })
+        .collect();
+    similar.sort_by_key(|(k, _)| k.len().abs_diff(key.len()));
+    similar
+        .into_iter()
+        .take(5)
+        .map(|(_, original)| original.clone())
+        .collect()
+}
+
+/// Names of everything installed, keyed by their normalized form: packages,
+/// desktop entries (file id, last reverse-DNS segment, `Exec` binary and
+/// `StartupWMClass`) and executables on `$PATH`.
+fn installed_names(manager: Option<&str>) -> HashMap<String, String> {
+    let mut names: Vec<String> = Vec::new();
+
+    let packages = match manager {
+        Some("dnf") => run("rpm", &["-qa", "--qf", "%{NAME}\n"]).unwrap_or_default(),
+        Some("apt") => run("dpkg-query", &["-W", "-f", "${Package}\n"]).unwrap_or_default(),
+        Some("pacman") => run("pacman", &["-Qq"]).unwrap_or_default(),
+        _ => String::new(),
+    };
+    names.extend(packages.lines().map(String::from));
+
+    for dir in desktop_entry_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "desktop") {
+                continue;
+            }
+            names.extend(desktop_entry_names(&path));
+        }
+    }
+
+    if let Some(paths) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&paths) {
+            if let Ok(entries) = fs::read_dir(dir) {
+                names.extend(
+                    entries
+                        .flatten()
+                        .map(|e| e.file_name().to_string_lossy().to_string()),
+                );
+            }
+        }
+    }
+    let mut known = HashMap::new();
+    for name in names {
+        let key = normalize(&name);
+        if !key.is_empty() {
+            known.entry(key).or_insert(name);
+        }
+    }
+    known
+}
+
+fn desktop_entry_dirs() -> Vec<PathBuf> {
+    let data = dirs::data_dir().unwrap_or_default();
+    vec![
+        PathBuf::from("/usr/share/applications"),
+        PathBuf::from("/usr/local/share/applications"),
+        data.join("applications"),
+        PathBuf::from("/var/lib/flatpak/exports/share/applications"),
+        data.join("flatpak/exports/share/applications"),
+        PathBuf::from("/var/lib/snapd/desktop/applications"),
+    ]
+}
+
+fn desktop_entry_names(path: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) {
+        if let Some(last) = stem.rsplit('.').next() {
+            names.push(last.to_string());
+        }
+        // Snap desktop files are named `<snap>_<app>.desktop`.
+        if let Some((snap, _)) = stem.split_once('_') {
+            names.push(snap.to_string());
+        }
+        names.push(stem);
+    }
+    let content = fs::read_to_string(path).unwrap_or_default();
+    for line in content.lines() {
+        if let Some(exec) = line.strip_prefix("Exec=") {
+            if let Some(binary) = exec
+                .split_whitespace()
+                .find(|arg| !arg.contains('=') && *arg != "env")
+            {
+                names.extend(
+                    Path::new(binary)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string()),
+                );
+            }
+        } else if let Some(class) = line.strip_prefix("StartupWMClass=") {
+            names.push(class.trim().to_string());
+        }
+    }
+    names
+}
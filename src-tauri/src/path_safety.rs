@@ -0,0 +1,351 @@
+//! Central safety policy for deleting paths.
+//!
+//! Every cleanup removes files through a [`PathPolicy`], which refuses a path
+//! unless all of these hold:
+//!
+//! - it has no `..` and, after resolving symlinks in its parent, lies strictly inside
+//!   one of the roots the cleaner is allowed to touch;
+//! - it is not a protected path or a directory above one, and not inside a
+//!   protected tree such as `/usr` or `~/.ssh`;
+//! - it is not a mount point and no filesystem is mounted below it, so a
+//!   recursive delete never crosses into another filesystem.
+//!
+//! Users extend the policy in Settings: [`PROTECTED_PATHS_SETTING`] adds
+//! protected trees and [`ALLOWED_ROOTS_SETTING`] adds directories outside
+//! the home directory in which project artifacts may be removed. Both hold a
+//! JSON array of paths; `~` is expanded.
+
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cleaners::expand_home;
+use crate::database::Database;
+use crate::system;
+
+pub const PROTECTED_PATHS_SETTING: &str = "protected_paths";
+pub const ALLOWED_ROOTS_SETTING: &str = "allowed_cleanup_roots";
+
+/// Never removed, and nothing inside them either.
+const PROTECTED_TREES: &[&str] = &[
+    "/bin",
+    "/boot",
+    "/dev",
+    "/etc",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/proc",
+    "/sbin",
+    "/sys",
+    "/usr",
+    "/var/lib",
+    "~/.ssh",
+    "~/.gnupg",
+    "~/.pki",
+    "~/.password-store",
+    "~/.local/share/keyrings",
+];
+
+/// Never removed themselves, nor is any directory above them; their
+/// contents may be cleaned.
+const PROTECTED_PATHS: &[&str] = &[
+    "/",
+    "/home",
+    "/root",
+    "/opt",
+    "/srv",
+    "/mnt",
+    "/media",
+    "/run",
+    "/run/media",
+    "/tmp",
+    "/var",
+    "/var/cache",
+    "/var/log",
+    "/var/tmp",
+    "~",
+    "~/.cache",
+    "~/.config",
+    "~/.local",
+    "~/.local/share",
+    "~/Desktop",
+    "~/Documents",
+    "~/Downloads",
+    "~/Music",
+    "~/Pictures",
+    "~/Public",
+    "~/Templates",
+    "~/Videos",
+];
+
+/// User additions, loaded from the settings table at startup and replaced
+/// whenever one of the two settings changes.
+static USER_RULES: RwLock<UserRules> = RwLock::new(UserRules {
+    protected_paths: Vec::new(),
+    allowed_roots: Vec::new(),
+});
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserRules {
+    pub protected_paths: Vec<String>,
+    pub allowed_roots: Vec<String>,
+}
+
+/// The effective policy, for display in Settings.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PathPolicyInfo {
+    pub protected_trees: Vec<String>,
+    pub protected_paths: Vec<String>,
+    pub user: UserRules,
+}
+
+pub fn get_path_policy() -> PathPolicyInfo {
+    PathPolicyInfo {
+        protected_trees: PROTECTED_TREES.iter().map(|p| p.to_string()).collect(),
+        protected_paths: PROTECTED_PATHS.iter().map(|p| p.to_string()).collect(),
+        user: user_rules(),
+    }
+}
+
+fn user_rules() -> UserRules {
+    USER_RULES
+        .read()
+        .map(|rules| rules.clone())
+        .unwrap_or_default()
+}
+
+/// Validates and applies `value` when `key` is one of the policy settings;
+/// other keys are ignored. Called before the setting is stored, so an
+/// invalid value is rejected rather than saved.
+pub fn apply_setting(key: &str, value: &str) -> Result<(), String> {
+    if key != PROTECTED_PATHS_SETTING && key != ALLOWED_ROOTS_SETTING {
+        return Ok(());
+    }
+    let paths: Vec<String> = serde_json::from_str(value)
+        .map_err(|e| format!("{key} must be a JSON array of paths: {e}"))?;
+    if let Some(bad) = paths.iter().find(|p| !expand_home(p).is_absolute()) {
+        return Err(format!("{key}: not an absolute path: {bad}"));
+    }
+    if key == ALLOWED_ROOTS_SETTING {
+        let probe = PathPolicy::new(&[]);
+        if let Some(bad) = paths
+            .iter()
+            .find(|p| probe.check_protected(&normalize(&expand_home(p))).is_err())
+        {
+            return Err(format!("{key}: {bad} is a protected location"));
+        }
+    }
+
+    let mut rules = USER_RULES.write().map_err(|e| format!("Lock error: {e}"))?;
+    if key == PROTECTED_PATHS_SETTING {
+        rules.protected_paths = paths;
+    } else {
+        rules.allowed_roots = paths;
+    }
+    Ok(())
+}
+
+/// Loads the user's policy settings; invalid values are logged and skipped.
+pub fn load_settings(db: &Database) {
+    for key in [PROTECTED_PATHS_SETTING, ALLOWED_ROOTS_SETTING] {
+        match db.get_setting(key) {
+            Ok(Some(value)) => {
+                if let Err(e) = apply_setting(key, &value) {
+                    log::warn!("Ignoring setting {e}");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Cannot read setting {key}: {e}"),
+        }
+    }
+}
+
+/// Roots in which user-chosen directories such as project artifacts may be
+/// removed: the home directory plus the user's allowed roots.
+pub fn user_roots() -> Vec<PathBuf> {
+    dirs::home_dir()
+        .into_iter()
+        .chain(user_rules().allowed_roots.iter().map(|p| expand_home(p)))
+        .collect()
+}
+
+/// A snapshot of the policy for one cleanup, limited to `roots`.
+pub struct PathPolicy {
+    roots: Vec<PathBuf>,
+    protected_trees: Vec<PathBuf>,
+    protected_paths: Vec<PathBuf>,
+    mounts: Vec<PathBuf>,
+}
+
+impl PathPolicy {
+    /// Policy allowing removals strictly inside `roots`. Roots that do not
+    /// exist are dropped.
+    pub fn new(roots: &[PathBuf]) -> Self {
+        let resolve_all = |paths: &mut dyn Iterator<Item = PathBuf>| -> Vec<PathBuf> {
+            let mut resolved = Vec::new();
+            for path in paths {
+                // Compare against both spellings: the canonical one catches
+                // symlinked locations, the lexical one covers missing paths.
+                let lexical = normalize(&path);
+                if let Ok(canonical) = fs::canonicalize(&lexical) {
+                    resolved.push(canonical);
+                }
+                resolved.push(lexical);
+            }
+            resolved.sort();
+            resolved.dedup();
+            resolved
+        };
+        let user = user_rules();
+        PathPolicy {
+            roots: roots
+                .iter()
+                .filter_map(|r| fs::canonicalize(r).ok())
+                .collect(),
+            protected_trees: resolve_all(
+                &mut PROTECTED_TREES
+                    .iter()
+                    .map(|p| p.to_string())
+                    .chain(user.protected_paths)
+                    .map(|p| expand_home(&p)),
+            ),
+            protected_paths: resolve_all(&mut PROTECTED_PATHS.iter().map(|p| expand_home(p))),
+            mounts: system::mounts().into_iter().map(|(m, _)| m).collect(),
+        }
+    }
+
+    /// Checks that `path` may be removed and returns it resolved.
+    pub fn check(&self, path: &Path) -> Result<PathBuf, String> {
+        let refuse = |reason: &str| format!("Refusing to remove {}: {reason}", path.display());
+        let resolved = resolve(path).map_err(|e| refuse(&e))?;
+        if !self
+            .roots
+            .iter()
+            .any(|root| resolved.starts_with(root) && resolved != *root)
+        {
+            return Err(refuse("outside the locations this cleanup may touch"));
+        }
+        self.check_protected(&resolved).map_err(|e| refuse(&e))?;
+        self.check_mounts(&resolved).map_err(|e| refuse(&e))?;
+        Ok(resolved)
+    }
+
+    /// Removes `path` (recursively for directories) after [`Self::check`].
+    /// A symlink is removed itself, never its target.
+    pub fn remove(&self, path: &Path) -> Result<(), String> {
+        let resolved = self.check(path)?;
+        let meta = fs::symlink_metadata(&resolved)
+            .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+        let result = if meta.is_dir() {
+            fs::remove_dir_all(&resolved)
+        } else {
+            fs::remove_file(&resolved)
+        };
+        result.map_err(|e| format!("Failed to remove {}: {e}", path.display()))
+    }
+
+    /// Removes `dir` only if it is empty, after [`Self::check`].
+    pub fn remove_empty_dir(&self, dir: &Path) -> Result<(), String> {
+        let resolved = self.check(dir)?;
+        fs::remove_dir(&resolved).map_err(|e| format!("Failed to remove {}: {e}", dir.display()))
+    }
+
+    /// Checks that the contents of `dir` may be removed as a whole: `dir`
+    /// lies inside one of the roots, is not inside a protected tree, and no
+    /// protected location lies below it. Each entry must still pass
+    /// [`Self::check`].
+    pub fn check_contents(&self, dir: &Path) -> Result<PathBuf, String> {
+        let refuse = |reason: &str| format!("Refusing to empty {}: {reason}", dir.display());
+        let resolved = fs::canonicalize(dir).map_err(|e| refuse(&e.to_string()))?;
+        if !self
+            .roots
+            .iter()
+            .any(|root| resolved.starts_with(root) && resolved != *root)
+        {
+            return Err(refuse("outside the locations this cleanup may touch"));
+        }
+        if let Some(protected) = self
+            .protected_trees
+            .iter()
+            .chain(&self.protected_paths)
+            .find(|p| {
+                resolved.starts_with(p) && self.protected_trees.contains(p)
+                    || p.starts_with(&resolved) && **p != resolved
+            })
+        {
+            return Err(refuse(&format!("{} is protected", protected.display())));
+        }
+        Ok(resolved)
+    }
+
+    fn check_protected(&self, resolved: &Path) -> Result<(), String> {
+        if let Some(tree) = self
+            .protected_trees
+            .iter()
+            .find(|t| resolved.starts_with(t) || t.starts_with(resolved))
+        {
+            return Err(format!("{} is protected", tree.display()));
+        }
+        if let Some(protected) = self
+            .protected_paths
+            .iter()
+            .find(|p| p.starts_with(resolved))
+        {
+            return Err(format!("{} is protected", protected.display()));
+        }
+        Ok(())
+    }
+
+    fn check_mounts(&self, resolved: &Path) -> Result<(), String> {
+        if let Some(mount) = self.mounts.iter().find(|m| m.starts_with(resolved)) {
+            return Err(format!("a filesystem is mounted at {}", mount.display()));
+        }
+        // Catches mounts made after the snapshot, or when /proc is missing.
+        let device = |p: &Path| fs::symlink_metadata(p).map(|m| m.dev()).ok();
+        let parent_device = resolved.parent().and_then(&device);
+        match (device(resolved), parent_device) {
+            (Some(dev), Some(parent)) if dev != parent => {
+                Err("it is the root of a mounted filesystem".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Resolves `path` as the kernel will when deleting it: symlinks in its
+/// parent are resolved, its last component is kept as is. Paths with `..`
+/// are rejected outright; no scanner produces them.
+fn resolve(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_absolute() {
+        return Err("not an absolute path".to_string());
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err("path contains `..`".to_string());
+    }
+    let name = match path.components().next_back() {
+        Some(Component::Normal(name)) => name,
+        _ => return Err("no file name".to_string()),
+    };
+    let parent = path.parent().ok_or("no parent directory")?;
+    let parent = fs::canonicalize(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+    Ok(parent.join(name))
+}
+
+/// Lexically removes `.` and `..` components.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
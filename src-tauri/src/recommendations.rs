@@ -0,0 +1,299 @@
+//! Validated execution of AI cleanup recommendations.
+//!
+//! The items of a recommendation come from the model and are not trusted.
+//! Before anything runs, every selected item must exist, the size the model
+//! claimed must roughly match the real size, and the item must map to an
+//! action. Caches, the Trash, the temp directories and Docker categories
+//! (`docker:images`, `docker:containers`, `docker:build-cache`) run their
+//! existing cleaners, and old kernels (`kernel:<package>`) are removed with
+//! the package manager; Docker volumes hold app data and are never pruned from
+//! a recommendation. Anything else depends on the candidate kind the
+//! recommendation was grounded on: project artifacts go through the artifact
+//! cleaner, and large files and duplicates are moved to the Trash through the
+//! path safety policy, never deleted outright. Items that fail a check are
+//! skipped and reported.
+
+use std::cell::OnceCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ai_client::{CleanupItem, CleanupRecommendation};
+use crate::artifacts;
+use crate::browsers::{self, BrowserInstall, DataCategory};
+use crate::caches;
+use crate::candidates::{self, CandidateKind};
+use crate::cleaners::{self, expand_home, measure_cleanup};
+use crate::docker::{self, DockerInfo};
+use crate::docker_prune::{self, PrunePolicy};
+use crate::docker_usage::UsageCategory;
+use crate::path_safety::{self, PathPolicy};
+use crate::system::{dir_size_and_count, CleanupResult};
+use crate::temp_files;
+use crate::trash;
+
+/// Claimed and real sizes within this many bytes always match, so small
+/// items and rounded figures pass.
+const SIZE_SLACK: u64 = 10 * 1024 * 1024;
+/// Otherwise the smaller size must be at least this fraction of the larger.
+const SIZE_RATIO: f64 = 0.5;
+
+const DOCKER_PREFIX: &str = "docker:";
+const KERNEL_PREFIX: &str = "kernel:";
+
+/// What a validated item runs.
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    Clean(Cleaner),
+    RemoveArtifact(PathBuf),
+    MoveToTrash(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Cleaner {
+    PackageCache(String),
+    BrowserCache(String),
+    Docker(String),
+    /// Package name and version of an old kernel.
+    Kernel(String, String),
+    Trash,
+    TempFiles,
+}
+
+/// Runs the `selected_items` (item paths) of `rec`. Fails only when no
+/// selected item passes validation.
+pub fn apply_recommendation(
+    rec: &CleanupRecommendation,
+    selected_items: &[String],
+) -> Result<CleanupResult, String> {
+    if selected_items.is_empty() {
+        return Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: "No items selected".to_string(),
+        });
+    }
+
+    let docker_info = OnceCell::new();
+    let installs = browsers::discover_installs();
+    let mut actions: Vec<Action> = Vec::new();
+    let mut skipped = Vec::new();
+    for selected in selected_items {
+        let Some(item) = rec.items.iter().find(|i| i.path == *selected) else {
+            skipped.push(format!("Skipped {selected}: not part of {}", rec.id));
+            continue;
+        };
+        match validate(&rec.category, item, &installs, &docker_info) {
+            Ok(action) if !actions.contains(&action) => actions.push(action),
+            Ok(_) => {}
+            Err(e) => skipped.push(format!("Skipped {selected}: {e}")),
+        }
+    }
+    if actions.is_empty() {
+        return Err(format!("Nothing to apply. {}", skipped.join("; ")));
+    }
+
+    let mut results = Vec::new();
+    let mut artifact_dirs = Vec::new();
+    let mut to_trash = Vec::new();
+    for action in actions {
+        match action {
+            Action::Clean(cleaner) => results.push(run_cleaner(&cleaner, &installs)),
+            Action::RemoveArtifact(path) => artifact_dirs.push(path.to_string_lossy().to_string()),
+            Action::MoveToTrash(path) => to_trash.push(path),
+        }
+    }
+    if !artifact_dirs.is_empty() {
+        results.push(artifacts::clean_project_artifacts(&artifact_dirs));
+    }
+    if !to_trash.is_empty() {
+        results.push(trash_paths(&to_trash));
+    }
+
+    let mut space_freed = 0;
+    let mut success = true;
+    let mut messages = Vec::new();
+    for result in results {
+        match result {
+            Ok(r) => {
+                space_freed += r.space_freed;
+                success &= r.success;
+                messages.push(r.message);
+            }
+            Err(e) => {
+                success = false;
+                messages.push(e);
+            }
+        }
+    }
+    messages.extend(skipped);
+    Ok(CleanupResult {
+        success,
+        space_freed,
+        message: messages.join("; "),
+    })
+}
+
+/// Checks one item and maps it to the action that cleans it.
+fn validate(
+    category: &str,
+    item: &CleanupItem,
+    installs: &[BrowserInstall],
+    docker_info: &OnceCell<Result<DockerInfo, String>>,
+) -> Result<Action, String> {
+    if let Some(target) = item.path.strip_prefix(DOCKER_PREFIX) {
+        let usage = match target {
+            "images" => UsageCategory::Images,
+            "containers" => UsageCategory::Containers,
+            "build-cache" => UsageCategory::BuildCache,
+            "volumes" => return Err("volumes may hold app data".to_string()),
+            _ => return Err(format!("unknown Docker category {target}")),
+        };
+        let info = docker_info
+            .get_or_init(|| docker::get_docker_info(None))
+            .as_ref()
+            .map_err(|e| e.clone())?;
+        let reclaimable = info
+            .disk_usage
+            .iter()
+            .find(|c| c.category == usage)
+            .map_or(0, |c| c.reclaimable);
+        check_size(item.size, reclaimable)?;
+        return Ok(Action::Clean(Cleaner::Docker(target.to_string())));
+    }
+
+    if let Some(package) = item.path.strip_prefix(KERNEL_PREFIX) {
+        let (package, version) = candidates::old_kernel_packages()
+            .into_iter()
+            .find(|(p, _)| p == package)
+            .ok_or_else(|| "not an old kernel".to_string())?;
+        check_size(
+            item.size,
+            dir_size_and_count(&candidates::kernel_modules_dir(&version)).0,
+        )?;
+        return Ok(Action::Clean(Cleaner::Kernel(package, version)));
+    }
+
+    let path = expand_home(&item.path);
+    let meta = fs::symlink_metadata(&path).map_err(|_| "path does not exist".to_string())?;
+
+    let registry = caches::registry();
+    if let Some(cleaner) = registry.iter().find(|c| c.paths().contains(&path)) {
+        check_size(item.size, cleaner.size())?;
+        return Ok(Action::Clean(Cleaner::PackageCache(
+            cleaner.name().to_string(),
+        )));
+    }
+    if let Some(install) = installs.iter().find(|i| i.cache_root == path) {
+        let size = install
+            .cache_dirs()
+            .iter()
+            .map(|d| dir_size_and_count(d).0)
+            .sum();
+        check_size(item.size, size)?;
+        return Ok(Action::Clean(Cleaner::BrowserCache(
+            install.display_name.clone(),
+        )));
+    }
+    if path == trash::home_trash() {
+        check_size(item.size, dir_size_and_count(&path).0)?;
+        return Ok(Action::Clean(Cleaner::Trash));
+    }
+    if path == Path::new("/tmp") || path == Path::new("/var/tmp") {
+        check_size(item.size, dir_size_and_count(&path).0)?;
+        return Ok(Action::Clean(Cleaner::TempFiles));
+    }
+
+    match CandidateKind::from_category(category) {
+        Some(CandidateKind::ProjectArtifact) => {
+            if artifacts::artifact_kind(&path).is_none() {
+                return Err("not a project artifact".to_string());
+            }
+            check_size(item.size, dir_size_and_count(&path).0)?;
+            Ok(Action::RemoveArtifact(path))
+        }
+        Some(CandidateKind::LargeFile | CandidateKind::Duplicate) => {
+            let resolved = PathPolicy::new(&path_safety::user_roots()).check(&path)?;
+            let size = if meta.is_dir() {
+                dir_size_and_count(&resolved).0
+            } else {
+                meta.len()
+            };
+            check_size(item.size, size)?;
+            Ok(Action::MoveToTrash(resolved))
+        }
+        _ => Err(format!(
+            "no cleaner matches it and category {category} allows no deletions"
+        )),
+    }
+}
+
+fn check_size(claimed: u64, actual: u64) -> Result<(), String> {
+    let (low, high) = (claimed.min(actual), claimed.max(actual));
+    if high - low <= SIZE_SLACK || low as f64 >= high as f64 * SIZE_RATIO {
+        Ok(())
+    } else {
+        Err(format!(
+            "claimed size {claimed} bytes does not match actual size {actual} bytes"
+        ))
+    }
+}
+
+fn run_cleaner(cleaner: &Cleaner, installs: &[BrowserInstall]) -> Result<CleanupResult, String> {
+    match cleaner {
+        Cleaner::PackageCache(name) => caches::clean_package_cache(name),
+        Cleaner::BrowserCache(name) => {
+            let install = installs
+                .iter()
+                .find(|i| i.display_name == *name)
+                .ok_or_else(|| format!("Browser not found: {name}"))?;
+            if install.is_running() {
+                return Err(format!("{name} is running; close it to clean its cache"));
+            }
+            browsers::clean_categories(install, None, &DataCategory::DEFAULT_CLEAN)
+        }
+        Cleaner::Docker(target) => {
+            docker_prune::prune_docker(target, &PrunePolicy::default(), None)
+        }
+        Cleaner::Kernel(package, version) => remove_kernel(package, version),
+        Cleaner::Trash => trash::empty_home_trash(),
+        Cleaner::TempFiles => temp_files::clean_temp_files(temp_files::DEFAULT_MAX_AGE_DAYS, false),
+    }
+}
+
+/// Removes an old kernel package with the package manager that installed it.
+fn remove_kernel(package: &str, version: &str) -> Result<CleanupResult, String> {
+    let cmd: Vec<String> = if package.starts_with("kernel-core-") {
+        ["dnf", "remove", "-y", package]
+    } else {
+        ["apt-get", "purge", "-y", package]
+    }
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    measure_cleanup(&[candidates::kernel_modules_dir(version)], || {
+        cleaners::run_clean_command(package, &cmd, true)
+    })
+}
+
+/// Moves large files and duplicates to the Trash, where they can still be
+/// restored; no space is freed until the Trash is emptied.
+fn trash_paths(paths: &[PathBuf]) -> Result<CleanupResult, String> {
+    let policy = PathPolicy::new(&path_safety::user_roots());
+    let mut moved = 0;
+    let mut messages = Vec::new();
+    for path in paths {
+        match trash::move_to_trash(&policy, path) {
+            Ok(_) => moved += 1,
+            Err(e) => messages.push(e),
+        }
+    }
+    messages.insert(
+        0,
+        format!("Moved {moved} of {} items to the Trash", paths.len()),
+    );
+    Ok(CleanupResult {
+        success: moved == paths.len(),
+        space_freed: 0,
+        message: messages.join("; "),
+    })
+}
@@ -0,0 +1,551 @@
+//! Flatpak and Snap disk usage.
+//!
+//! Lists installed Flatpak apps and runtimes (user, system and custom
+//! installations from `/etc/flatpak/installations.d`),
+//! per-app data under `~/.var/app` and `~/snap`, and every snap revision
+//! snapd keeps around. The leftovers these platforms accumulate are unused
+//! runtimes after app removals or upgrades, disabled snap revisions after
+//! each refresh, and data directories of apps that are no longer installed.
+//!
+//! Flatpak deployments are hard links into the OSTree repository, so a
+//! ref's size is what removing it frees only when no other ref shares its
+//! objects.
+//!
+//! App data is only called orphaned when the list of installed apps could be
+//! read: if snapd does not answer or a Flatpak installation is unreadable,
+//! every data directory of that platform counts as installed.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cleaners::{
+    command_exists, measure_cleanup, measured_clean, run_clean_command, CacheCleaner,
+};
+use crate::path_safety::PathPolicy;
+use crate::system::{dir_size_and_count, CleanupResult};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FlatpakRef {
+    /// `id/arch/branch`, as accepted by `flatpak uninstall`.
+    pub ref_id: String,
+    pub id: String,
+    /// `app` or `runtime`.
+    pub kind: String,
+    /// `user`, `system` or the name of a custom installation.
+    pub installation: String,
+    pub path: String,
+    pub size: u64,
+    /// Runtime not needed by any installed app or runtime. This is an
+    /// estimate; `flatpak uninstall --unused` makes the final decision.
+    pub unused: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapRevision {
+    pub name: String,
+    pub version: String,
+    pub revision: String,
+    pub disabled: bool,
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppDataDir {
+    pub app_id: String,
+    /// `flatpak` or `snap`.
+    pub platform: String,
+    pub path: String,
+    pub size: u64,
+    /// Also `true` when the installed apps could not be listed.
+    pub installed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SandboxedAppsReport {
+    pub flatpak_refs: Vec<FlatpakRef>,
+    pub snap_revisions: Vec<SnapRevision>,
+    pub app_data: Vec<AppDataDir>,
+}
+
+/// Cleaners registered with the package cache listing.
+pub fn cleaners() -> Vec<Box<dyn CacheCleaner>> {
+    vec![
+        Box::new(FlatpakUnusedCleaner),
+        Box::new(SnapRevisionsCleaner),
+    ]
+}
+
+pub fn get_sandboxed_apps() -> Result<SandboxedAppsReport, String> {
+    Ok(SandboxedAppsReport {
+        flatpak_refs: flatpak_refs(),
+        snap_revisions: snap_revisions().unwrap_or_default(),
+        app_data: app_data_dirs(),
+    })
+}
+
+// --- Flatpak ---
+
+const CUSTOM_INSTALLATIONS_DIR: &str = "/etc/flatpak/installations.d";
+
+/// The user and system installations, then every custom installation.
+fn flatpak_installations() -> Vec<(String, PathBuf)> {
+    let user = dirs::data_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".local/share"))
+        .join("flatpak");
+    let mut installations = vec![
+        ("user".to_string(), user),
+        ("system".to_string(), PathBuf::from("/var/lib/flatpak")),
+    ];
+    let mut confs: Vec<PathBuf> = fs::read_dir(CUSTOM_INSTALLATIONS_DIR)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    confs.sort();
+    for conf in confs
+        .iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "conf"))
+    {
+        let content = fs::read_to_string(conf).unwrap_or_default();
+        installations.extend(parse_installations_conf(&content));
+    }
+    installations
+}
+
+/// `[Installation "name"]` groups and their `Path=`.
+fn parse_installations_conf(content: &str) -> Vec<(String, PathBuf)> {
+    let mut found = Vec::new();
+    let mut name = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(group) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            name = group
+                .strip_prefix("Installation ")
+                .map(|n| n.trim().trim_matches('"').to_string());
+        } else if let (Some(n), Some(path)) = (&name, line.strip_prefix("Path=")) {
+            found.push((n.clone(), PathBuf::from(path.trim())));
+        }
+    }
+    found
+}
+
+/// A deployed ref: installation, kind, `id/arch/branch` and its directory.
+struct Deployment {
+    installation: String,
+    kind: &'static str,
+    id: String,
+    ref_id: String,
+    dir: PathBuf,
+}
+
+impl Deployment {
+    fn metadata(&self) -> String {
+        fs::read_to_string(self.dir.join("active/metadata")).unwrap_or_default()
+    }
+}
+
+/// Walks `<installation>/{app,runtime}/<id>/<arch>/<branch>`, skipping the
+/// `current` symlinks and branches without an `active` deployment. Fails when
+/// an installation exists but cannot be read.
+fn deployments() -> Result<Vec<Deployment>, String> {
+    let mut found = Vec::new();
+    for (installation, root) in flatpak_installations() {
+        for kind in ["app", "runtime"] {
+            let kind_dir = root.join(kind);
+            if let Err(e) = fs::read_dir(&kind_dir) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    return Err(format!("Cannot read {}: {e}", kind_dir.display()));
+                }
+            }
+            for id_dir in real_subdirs(&kind_dir) {
+                let id = file_name(&id_dir);
+                for arch_dir in real_subdirs(&id_dir) {
+                    for branch_dir in real_subdirs(&arch_dir) {
+                        if !branch_dir.join("active").exists() {
+                            continue;
+                        }
+                        found.push(Deployment {
+                            installation: installation.clone(),
+                            kind,
+                            ref_id: format!(
+                                "{id}/{}/{}",
+                                file_name(&arch_dir),
+                                file_name(&branch_dir)
+                            ),
+                            id: id.clone(),
+                            dir: branch_dir,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn real_subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir() && !p.is_symlink())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn flatpak_refs() -> Vec<FlatpakRef> {
+    let deployments = deployments().unwrap_or_else(|e| {
+        log::warn!("Listing Flatpak refs: {e}");
+        Vec::new()
+    });
+    let used = used_runtimes(&deployments);
+    let mut refs: Vec<FlatpakRef> = deployments
+        .par_iter()
+        .map(|d| FlatpakRef {
+            ref_id: d.ref_id.clone(),
+            id: d.id.clone(),
+            kind: d.kind.to_string(),
+            installation: d.installation.clone(),
+            path: d.dir.to_string_lossy().to_string(),
+            size: dir_size_and_count(&d.dir).0,
+            unused: d.kind == "runtime" && !used.contains(&d.ref_id),
+        })
+        .collect();
+    refs.sort_by_key(|r| std::cmp::Reverse(r.size));
+    refs
+}
+
+/// Runtime refs reachable from installed apps: each app's `runtime=`, and
+/// the extensions (`[Extension <id>]`) declared by apps and used runtimes.
+fn used_runtimes(deployments: &[Deployment]) -> HashSet<String> {
+    let runtimes: Vec<&Deployment> = deployments.iter().filter(|d| d.kind == "runtime").collect();
+    let mut used = HashSet::new();
+    let mut pending: Vec<String> = deployments
+        .iter()
+        .filter(|d| d.kind == "app")
+        .map(Deployment::metadata)
+        .collect();
+
+    while let Some(metadata) = pending.pop() {
+        let (runtime, extensions) = parse_flatpak_metadata(&metadata);
+        for runtime_dep in runtimes.iter().filter(|r| {
+            runtime.as_deref() == Some(r.ref_id.as_str())
+                || extensions
+                    .iter()
+                    .any(|ext| r.id == *ext || r.id.starts_with(&format!("{ext}.")))
+        }) {
+            if used.insert(runtime_dep.ref_id.clone()) {
+                pending.push(runtime_dep.metadata());
+            }
+        }
+    }
+    used
+}
+
+/// The `runtime=` ref of an app's `[Application]` group and the extension
+/// point names of every `[Extension ...]` group.
+fn parse_flatpak_metadata(metadata: &str) -> (Option<String>, Vec<String>) {
+    let mut runtime = None;
+    let mut extensions = Vec::new();
+    let mut group = "";
+    for line in metadata.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            group = name;
+            if let Some(ext) = name.strip_prefix("Extension ") {
+                extensions.push(ext.trim().to_string());
+            }
+        } else if group == "Application" {
+            if let Some(v) = line.strip_prefix("runtime=") {
+                runtime = Some(v.trim().to_string());
+            }
+        }
+    }
+    (runtime, extensions)
+}
+
+/// `flatpak uninstall --unused` for every installation. System-wide ones go
+/// through polkit, so they may prompt.
+struct FlatpakUnusedCleaner;
+
+impl CacheCleaner for FlatpakUnusedCleaner {
+    fn name(&self) -> &str {
+        "flatpak"
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        flatpak_refs()
+            .into_iter()
+            .filter(|r| r.unused)
+            .map(|r| PathBuf::from(r.path))
+            .collect()
+    }
+
+    fn detect(&self) -> bool {
+        command_exists("flatpak")
+    }
+
+    fn clean(&self) -> Result<CleanupResult, String> {
+        let mut messages = Vec::new();
+        let mut success = true;
+        for (name, _) in flatpak_installations() {
+            let installation = match name.as_str() {
+                "user" => "--user".to_string(),
+                "system" => "--system".to_string(),
+                _ => format!("--installation={name}"),
+            };
+            let cmd = [
+                "flatpak",
+                "uninstall",
+                &installation,
+                "--unused",
+                "-y",
+                "--noninteractive",
+            ]
+            .map(String::from);
+            let result = run_clean_command("flatpak", &cmd, false)?;
+            success &= result.success;
+            if !result.message.trim().is_empty() {
+                messages.push(result.message.trim().to_string());
+            }
+        }
+        Ok(CleanupResult {
+            success,
+            space_freed: 0,
+            message: if messages.is_empty() {
+                "No unused Flatpak runtimes".to_string()
+            } else {
+                messages.join("; ")
+            },
+        })
+    }
+}
+
+pub fn remove_unused_flatpak_runtimes() -> Result<CleanupResult, String> {
+    if !command_exists("flatpak") {
+        return Err("Flatpak is not installed".to_string());
+    }
+    measured_clean(&FlatpakUnusedCleaner)
+}
+
+// --- Snap ---
+
+const SNAP_DIR: &str = "/var/lib/snapd/snaps";
+
+/// Every revision listed by `snap list --all`, sized from its `.snap` file.
+/// Fails when `snap` cannot run or exits non-zero, e.g. with snapd stopped.
+fn snap_revisions() -> Result<Vec<SnapRevision>, String> {
+    let output = Command::new("snap")
+        .args(["list", "--all"])
+        .output()
+        .map_err(|e| format!("Failed to run snap: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "snap list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            // Name  Version  Rev  Tracking  Publisher  Notes
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            let (name, version, revision) = (cols.first()?, cols.get(1)?, cols.get(2)?);
+            let disabled = cols
+                .last()
+                .is_some_and(|notes| notes.split(',').any(|n| n == "disabled"));
+            let path = PathBuf::from(format!("{SNAP_DIR}/{name}_{revision}.snap"));
+            Some(SnapRevision {
+                name: name.to_string(),
+                version: version.to_string(),
+                revision: revision.to_string(),
+                disabled,
+                size: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Removes disabled snap revisions that snapd keeps after each refresh.
+struct SnapRevisionsCleaner;
+
+impl CacheCleaner for SnapRevisionsCleaner {
+    fn name(&self) -> &str {
+        "snap"
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        snap_revisions()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| r.disabled)
+            .map(|r| PathBuf::from(r.path))
+            .collect()
+    }
+
+    fn detect(&self) -> bool {
+        command_exists("snap")
+    }
+
+    fn size(&self) -> u64 {
+        self.paths()
+            .iter()
+            .filter_map(|p| fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum()
+    }
+
+    fn requires_root(&self) -> bool {
+        true
+    }
+
+    fn clean(&self) -> Result<CleanupResult, String> {
+        let mut messages = Vec::new();
+        let mut success = true;
+        for rev in snap_revisions()?.into_iter().filter(|r| r.disabled) {
+            let cmd = [
+                "snap",
+                "remove",
+                &rev.name,
+                &format!("--revision={}", rev.revision),
+            ]
+            .map(String::from);
+            let result = run_clean_command("snap", &cmd, true)?;
+            success &= result.success;
+            messages.push(format!(
+                "{} rev {}: {}",
+                rev.name,
+                rev.revision,
+                result.message.trim()
+            ));
+        }
+        Ok(CleanupResult {
+            success,
+            space_freed: 0,
+            message: if messages.is_empty() {
+                "No disabled snap revisions".to_string()
+            } else {
+                messages.join("; ")
+            },
+        })
+    }
+}
+
+pub fn remove_disabled_snap_revisions() -> Result<CleanupResult, String> {
+    if !command_exists("snap") {
+        return Err("Snap is not installed".to_string());
+    }
+    measured_clean(&SnapRevisionsCleaner)
+}
+
+// --- Per-app data ---
+
+fn app_data_dirs() -> Vec<AppDataDir> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let flatpak_apps = deployments().map(|deployments| {
+        deployments
+            .into_iter()
+            .filter(|d| d.kind == "app")
+            .map(|d| d.id)
+            .collect()
+    });
+    let snaps = snap_revisions().map(|revisions| revisions.into_iter().map(|r| r.name).collect());
+
+    let mut dirs: Vec<(PathBuf, &str, bool)> = Vec::new();
+    let flatpak_apps = known_installed("Flatpak", flatpak_apps);
+    for dir in real_subdirs(&home.join(".var/app")) {
+        let installed = flatpak_apps
+            .as_ref()
+            .is_none_or(|apps| apps.contains(&file_name(&dir)));
+        dirs.push((dir, "flatpak", installed));
+    }
+    let snaps = known_installed("Snap", snaps);
+    for dir in real_subdirs(&home.join("snap")) {
+        let installed = snaps
+            .as_ref()
+            .is_none_or(|names| names.contains(&file_name(&dir)));
+        dirs.push((dir, "snap", installed));
+    }
+
+    let mut data: Vec<AppDataDir> = dirs
+        .par_iter()
+        .map(|(dir, platform, installed)| AppDataDir {
+            app_id: file_name(dir),
+            platform: platform.to_string(),
+            path: dir.to_string_lossy().to_string(),
+            size: dir_size_and_count(dir).0,
+            installed: *installed,
+        })
+        .collect();
+    data.sort_by_key(|d| std::cmp::Reverse(d.size));
+    data
+}
+
+/// The installed app names of a platform, or `None` when they are unknown:
+/// the listing failed or came back empty.
+fn known_installed(
+    platform: &str,
+    listed: Result<HashSet<String>, String>,
+) -> Option<HashSet<String>> {
+    match listed {
+        Ok(names) if !names.is_empty() => Some(names),
+        Ok(_) => {
+            log::info!("No installed {platform} apps listed; not flagging orphaned data");
+            None
+        }
+        Err(e) => {
+            log::warn!("{platform} apps unknown, not flagging orphaned data: {e}");
+            None
+        }
+    }
+}
+
+/// Removes the data directories of the uninstalled apps `app_ids`. Data of
+/// installed apps, or of apps whose platform could not be listed, is never
+/// removed.
+pub fn clean_orphaned_app_data(app_ids: &[String]) -> Result<CleanupResult, String> {
+    if app_ids.is_empty() {
+        return Err("No apps selected".to_string());
+    }
+    let orphans: Vec<AppDataDir> = app_data_dirs()
+        .into_iter()
+        .filter(|d| !d.installed && app_ids.contains(&d.app_id))
+        .collect();
+    if let Some(missing) = app_ids
+        .iter()
+        .find(|id| !orphans.iter().any(|o| o.app_id == **id))
+    {
+        return Err(format!("No data of an uninstalled app named {missing}"));
+    }
+    let targets: Vec<PathBuf> = orphans.iter().map(|d| PathBuf::from(&d.path)).collect();
+    let home = dirs::home_dir().unwrap_or_default();
+    let policy = PathPolicy::new(&[home.join(".var/app"), home.join("snap")]);
+    measure_cleanup(&targets, || {
+        let mut removed = 0;
+        let mut messages = Vec::new();
+        for path in &targets {
+            match policy.remove(path) {
+                Ok(_) => removed += 1,
+                Err(e) => messages.push(e),
+            }
+        }
+        messages.insert(0, format!("Removed data of {removed} uninstalled apps"));
+        Ok(CleanupResult {
+            success: removed == targets.len(),
+            space_freed: 0,
+            message: messages.join("; "),
+        })
+    })
+}
@@ -5,7 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -97,6 +97,60 @@ fn read_kernel_version() -> String {
         .to_string()
 }
 
+/// Real uid of this process, from `/proc/self/status`.
+pub fn current_uid() -> Option<u32> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Mount points and filesystem types from `/proc/mounts`; empty when it
+/// cannot be read.
+pub fn mounts() -> Vec<(PathBuf, String)> {
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(1)?;
+            let fs_type = fields.next()?;
+            Some((unescape_mount(mount_point), fs_type.to_string()))
+        })
+        .collect()
+}
+
+/// `/proc/mounts` escapes space, tab, newline and backslash as `\ooo`.
+fn unescape_mount(field: &str) -> PathBuf {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).and_then(|d| {
+            std::str::from_utf8(d)
+                .ok()
+                .and_then(|d| u8::from_str_radix(d, 8).ok())
+        });
+        match octal {
+            Some(b) if bytes[i] == b'\\' => {
+                out.push(b);
+                i += 4;
+            }
+            _ => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(String::from_utf8_lossy(&out).to_string())
+}
+
 fn read_memory_info() -> (u64, u64, u64) {
     let content = match fs::read_to_string("/proc/meminfo") {
         Ok(c) => c,
@@ -125,7 +179,8 @@ fn parse_meminfo_kb(val: &str) -> u64 {
         .unwrap_or(0)
 }
 
-fn read_disk_info(mount_point: &str) -> (u64, u64, u64) {
+/// Returns `(total, used, available)` bytes of the filesystem holding `mount_point`.
+pub fn read_disk_info(mount_point: &str) -> (u64, u64, u64) {
     let output = Command::new("df")
         .args(["--block-size=1", mount_point])
         .output();
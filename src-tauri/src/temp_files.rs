@@ -0,0 +1,236 @@
+//! Age-based cleanup of `/tmp`, `/var/tmp` and the user's Trash.
+//!
+//! Follows the rules `systemd-tmpfiles --clean` applies: an entry's age is the
+//! newest of its access, modification and change times, other filesystems
+//! mounted below a temp directory are not entered, and directories are only
+//! removed once empty. On top of that, only entries owned by the current user
+//! are touched, and sockets, FIFOs, lock files and files another process
+//! holds open are always kept. Cleaning every user's files is left to
+//! `systemd-tmpfiles` itself through the privileged path. Trash items are
+//! aged by their recorded deletion date instead (see [`crate::trash`]).
+
+use std::collections::HashSet;
+use std::fs;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::cleaners::{measure_cleanup, run_clean_command};
+use crate::path_safety::PathPolicy;
+use crate::system::{self, dir_size_and_count, CleanupResult};
+use crate::trash::{self, TrashItem};
+
+/// Same default as the `/tmp` entry in systemd's `tmp.conf`.
+pub const DEFAULT_MAX_AGE_DAYS: u64 = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TempLocation {
+    pub path: String,
+    pub size: u64,
+    pub file_count: u64,
+    /// Bytes in files that are old enough and safe to remove.
+    pub reclaimable: u64,
+    pub reclaimable_count: u64,
+}
+
+fn temp_roots() -> Vec<PathBuf> {
+    vec![PathBuf::from("/tmp"), PathBuf::from("/var/tmp")]
+}
+
+/// Sizes each temp location and what a clean with `days` would remove. The
+/// Trash is reported as one location covering every mount's trash.
+pub fn get_temp_usage(days: u64) -> Result<Vec<TempLocation>, String> {
+    let scan = Scan::new(days);
+    let mut locations: Vec<TempLocation> = temp_roots()
+        .into_iter()
+        .filter(|root| root.exists())
+        .map(|root| {
+            let (size, file_count) = dir_size_and_count(&root);
+            let candidates = scan.candidates(&root);
+            TempLocation {
+                path: root.to_string_lossy().to_string(),
+                size,
+                file_count,
+                reclaimable: candidates.files.iter().map(|(_, len)| len).sum(),
+                reclaimable_count: candidates.files.len() as u64,
+            }
+        })
+        .collect();
+
+    let items: Vec<TrashItem> = trash::get_trash_contents()?
+        .into_iter()
+        .flat_map(|dir| dir.items)
+        .collect();
+    let old: Vec<&TrashItem> = items
+        .iter()
+        .filter(|i| i.deleted_at != 0 && i.deleted_at < scan.cutoff)
+        .collect();
+    locations.push(TempLocation {
+        path: trash::home_trash().to_string_lossy().to_string(),
+        size: items.iter().map(|i| i.size).sum(),
+        file_count: items.len() as u64,
+        reclaimable: old.iter().map(|i| i.size).sum(),
+        reclaimable_count: old.len() as u64,
+    });
+    Ok(locations)
+}
+
+/// Removes the current user's temp files older than `days` and Trash items
+/// deleted more than `days` ago.
+///
+/// With `privileged`, `/tmp` and `/var/tmp` are instead handed to
+/// `sudo -n systemd-tmpfiles --clean`, which cleans every user's files using
+/// the ages configured in `tmpfiles.d` rather than `days`.
+pub fn clean_temp_files(days: u64, privileged: bool) -> Result<CleanupResult, String> {
+    let roots: Vec<PathBuf> = temp_roots().into_iter().filter(|r| r.exists()).collect();
+    let mut result = measure_cleanup(&roots, || {
+        if privileged {
+            let cmd = ["systemd-tmpfiles", "--clean"].map(String::from);
+            let result = run_clean_command("temp files", &cmd, true)?;
+            if !result.success {
+                return Err(format!(
+                    "systemd-tmpfiles --clean failed: {}",
+                    result.message
+                ));
+            }
+            return Ok(CleanupResult {
+                success: true,
+                space_freed: 0,
+                message: "systemd-tmpfiles cleaned /tmp and /var/tmp".to_string(),
+            });
+        }
+
+        let scan = Scan::new(days);
+        let policy = PathPolicy::new(&roots);
+        let mut removed = 0;
+        let mut messages = Vec::new();
+        for root in &roots {
+            let candidates = scan.candidates(root);
+            for (path, _) in &candidates.files {
+                match policy.remove(path) {
+                    Ok(_) => removed += 1,
+                    Err(e) => messages.push(e),
+                }
+            }
+            // Deepest first, so parents are empty by the time they are tried;
+            // directories that still hold kept files simply fail to go.
+            for dir in candidates.dirs.iter().rev() {
+                let _ = policy.remove_empty_dir(dir);
+            }
+        }
+        messages.insert(0, format!("Removed {removed} files older than {days} days"));
+        Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: messages.join("; "),
+        })
+    })?;
+
+    let trashed = trash::empty_trash(Some(days))?;
+    result.space_freed += trashed.space_freed;
+    result.message = format!("{}; {}", result.message, trashed.message);
+    Ok(result)
+}
+
+#[derive(Default)]
+struct Candidates {
+    files: Vec<(PathBuf, u64)>,
+    /// Old directories in walk order (parents before children).
+    dirs: Vec<PathBuf>,
+}
+
+/// State shared by one pass over the temp roots.
+struct Scan {
+    uid: Option<u32>,
+    cutoff: i64,
+    open_files: HashSet<PathBuf>,
+}
+
+impl Scan {
+    fn new(days: u64) -> Self {
+        Scan {
+            uid: system::current_uid(),
+            cutoff: chrono::Utc::now().timestamp() - (days as i64) * 86_400,
+            open_files: open_files(),
+        }
+    }
+
+    fn candidates(&self, root: &Path) -> Candidates {
+        let mut found = Candidates::default();
+        let Some(uid) = self.uid else {
+            return found;
+        };
+        if root.is_symlink() {
+            return found;
+        }
+        let mut walker = WalkDir::new(root)
+            .follow_links(false)
+            .same_file_system(true)
+            .into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else { continue };
+            if entry.depth() == 0 {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            if meta.uid() != uid {
+                if meta.is_dir() {
+                    walker.skip_current_dir();
+                }
+                continue;
+            }
+
+            let file_type = meta.file_type();
+            let old = entry_age(&meta) < self.cutoff;
+            if file_type.is_dir() {
+                if old {
+                    found.dirs.push(entry.into_path());
+                }
+            } else if old
+                && !file_type.is_socket()
+                && !file_type.is_fifo()
+                && !file_type.is_block_device()
+                && !file_type.is_char_device()
+                && !is_lock_file(entry.path())
+                && !self.open_files.contains(entry.path())
+            {
+                found.files.push((entry.into_path(), meta.len()));
+            }
+        }
+        found
+    }
+}
+
+/// Newest of atime, mtime and ctime, as used by `systemd-tmpfiles`.
+fn entry_age(meta: &fs::Metadata) -> i64 {
+    meta.atime().max(meta.mtime()).max(meta.ctime())
+}
+
+/// Lock and pid files whose mere presence carries meaning to their owner,
+/// e.g. `.X0-lock`, `foo.lock`, `foo.lck` or `foo.pid`.
+fn is_lock_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    name.ends_with("-lock")
+        || name == "LOCK"
+        || path
+            .extension()
+            .is_some_and(|ext| ext == "lock" || ext == "lck" || ext == "pid")
+}
+
+/// Every file some process has open, as far as `/proc/*/fd` is readable to
+/// us (other users' processes are not, but neither are their files ours).
+fn open_files() -> HashSet<PathBuf> {
+    let Ok(procs) = fs::read_dir("/proc") else {
+        return HashSet::new();
+    };
+    procs
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().parse::<u32>().is_ok())
+        .filter_map(|e| fs::read_dir(e.path().join("fd")).ok())
+        .flat_map(|fds| fds.flatten().filter_map(|fd| fs::read_link(fd.path()).ok()))
+        .collect()
+}
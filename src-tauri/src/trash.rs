@@ -0,0 +1,447 @@
+//! freedesktop.org Trash support.
+//!
+//! Covers the home trash (`$XDG_DATA_HOME/Trash`) and the per-mount trash
+//! directories file managers create on other filesystems: `$top/.Trash/$uid`
+//! when the admin provided a shared sticky `.Trash`, and `$top/.Trash-$uid`
+//! otherwise. Each trashed item lives in `files/` with a matching
+//! `info/<name>.trashinfo` recording its original path and deletion date.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::cleaners::measure_cleanup;
+use crate::path_safety::{self, PathPolicy};
+use crate::system::{self, dir_size_and_count, CleanupResult};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashItem {
+    /// Location inside the trash's `files/` directory; identifies the item.
+    pub path: String,
+    pub original_path: String,
+    /// `DeletionDate` as written in the `.trashinfo` file.
+    pub deletion_date: String,
+    /// Unix timestamp of the deletion, 0 when the date is missing.
+    pub deleted_at: i64,
+    pub size: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashDir {
+    pub path: String,
+    pub mount_point: String,
+    /// Whether this is the home trash rather than a per-mount one.
+    pub is_home: bool,
+    pub size: u64,
+    pub items: Vec<TrashItem>,
+}
+
+/// Filesystems that never hold user trash; `autofs` is skipped so that
+/// probing does not trigger mounts.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "proc",
+    "sysfs",
+    "devtmpfs",
+    "devpts",
+    "cgroup",
+    "cgroup2",
+    "securityfs",
+    "pstore",
+    "bpf",
+    "debugfs",
+    "tracefs",
+    "configfs",
+    "fusectl",
+    "mqueue",
+    "hugetlbfs",
+    "autofs",
+    "binfmt_misc",
+    "efivarfs",
+];
+
+pub fn home_trash() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".local/share"))
+        .join("Trash")
+}
+
+/// Every existing trash directory of the current user with the mount point
+/// it belongs to, home trash first.
+fn trash_dirs() -> Vec<(PathBuf, PathBuf, bool)> {
+    let mounts = mount_points();
+    let mut found = Vec::new();
+
+    let home = home_trash();
+    if home.is_dir() {
+        let mount = mounts
+            .iter()
+            .filter(|m| home.starts_with(m))
+            .max_by_key(|m| m.as_os_str().len())
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from("/"));
+        found.push((home.clone(), mount, true));
+    }
+
+    let Some(uid) = system::current_uid() else {
+        return found;
+    };
+    for top in mounts {
+        let shared = top.join(".Trash");
+        let candidates = [
+            is_valid_shared_trash(&shared).then(|| shared.join(uid.to_string())),
+            Some(top.join(format!(".Trash-{uid}"))),
+        ];
+        for dir in candidates.into_iter().flatten() {
+            if dir.is_dir() && !dir.is_symlink() && !found.iter().any(|(d, _, _)| *d == dir) {
+                found.push((dir, top.clone(), false));
+            }
+        }
+    }
+    found
+}
+
+/// The spec only trusts a shared `$top/.Trash` that is a real directory with
+/// the sticky bit set.
+fn is_valid_shared_trash(dir: &Path) -> bool {
+    fs::symlink_metadata(dir)
+        .map(|m| m.is_dir() && m.permissions().mode() & 0o1000 != 0)
+        .unwrap_or(false)
+}
+
+fn mount_points() -> Vec<PathBuf> {
+    let mounts = system::mounts();
+    if mounts.is_empty() {
+        return vec![PathBuf::from("/")];
+    }
+    mounts
+        .into_iter()
+        .filter(|(_, fs_type)| !PSEUDO_FILESYSTEMS.contains(&fs_type.as_str()))
+        .map(|(mount_point, _)| mount_point)
+        .collect()
+}
+
+/// Lists every trash directory with its items, newest deletion first.
+pub fn get_trash_contents() -> Result<Vec<TrashDir>, String> {
+    Ok(trash_dirs()
+        .into_iter()
+        .map(|(dir, mount, is_home)| {
+            let mut items = read_items(&dir, &mount, is_home);
+            items.sort_by_key(|i| std::cmp::Reverse(i.deleted_at));
+            TrashDir {
+                path: dir.to_string_lossy().to_string(),
+                mount_point: mount.to_string_lossy().to_string(),
+                is_home,
+                size: items.iter().map(|i| i.size).sum(),
+                items,
+            }
+        })
+        .collect())
+}
+
+fn read_items(trash: &Path, mount: &Path, is_home: bool) -> Vec<TrashItem> {
+    let Ok(entries) = fs::read_dir(trash.join("files")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let info = fs::read_to_string(info_path(trash, &name)).unwrap_or_default();
+            let (original, deletion_date) = parse_trash_info(&info);
+            // Per-mount trash stores paths relative to the mount's top dir.
+            let original_path = match original {
+                Some(p) if !is_home && !p.starts_with('/') => mount.join(p),
+                Some(p) => PathBuf::from(p),
+                None => PathBuf::new(),
+            };
+            let is_dir = path.is_dir() && !path.is_symlink();
+            TrashItem {
+                size: if is_dir {
+                    dir_size_and_count(&path).0
+                } else {
+                    fs::symlink_metadata(&path).map(|m| m.len()).unwrap_or(0)
+                },
+                is_dir,
+                original_path: original_path.to_string_lossy().to_string(),
+                deleted_at: parse_deletion_date(&deletion_date),
+                deletion_date,
+                path: path.to_string_lossy().to_string(),
+            }
+        })
+        .collect()
+}
+
+fn info_path(trash: &Path, name: &str) -> PathBuf {
+    trash.join("info").join(format!("{name}.trashinfo"))
+}
+
+/// Returns the percent-decoded `Path` and the raw `DeletionDate` of a
+/// `.trashinfo` file.
+fn parse_trash_info(content: &str) -> (Option<String>, String) {
+    let mut path = None;
+    let mut date = String::new();
+    let mut in_section = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line == "[Trash Info]";
+        } else if !in_section {
+            continue;
+        } else if let Some(v) = line.strip_prefix("Path=") {
+            path = Some(percent_decode(v));
+        } else if let Some(v) = line.strip_prefix("DeletionDate=") {
+            date = v.to_string();
+        }
+    }
+    (path, date)
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| {
+            std::str::from_utf8(h)
+                .ok()
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        });
+        match hex {
+            Some(b) if bytes[i] == b'%' => {
+                out.push(b);
+                i += 3;
+            }
+            _ => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// `DeletionDate` is local time in `YYYY-MM-DDThh:mm:ss` form.
+fn parse_deletion_date(date: &str) -> i64 {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.timestamp())
+        .unwrap_or(0)
+}
+
+/// Permanently deletes trashed items, from every trash directory. With
+/// `older_than_days`, only items deleted at least that long ago go; items
+/// without a readable deletion date are then kept.
+pub fn empty_trash(older_than_days: Option<u64>) -> Result<CleanupResult, String> {
+    empty_dirs(get_trash_contents()?, older_than_days)
+}
+
+/// Permanently deletes every item of the home trash only, leaving the
+/// per-mount trash directories alone.
+pub fn empty_home_trash() -> Result<CleanupResult, String> {
+    let dirs = get_trash_contents()?
+        .into_iter()
+        .filter(|d| d.is_home)
+        .collect();
+    empty_dirs(dirs, None)
+}
+
+fn empty_dirs(dirs: Vec<TrashDir>, older_than_days: Option<u64>) -> Result<CleanupResult, String> {
+    let cutoff = older_than_days.map(|d| chrono::Utc::now().timestamp() - (d as i64) * 86_400);
+    let roots: Vec<PathBuf> = dirs.iter().map(|d| PathBuf::from(&d.path)).collect();
+
+    measure_cleanup(&roots, || {
+        let mut removed = 0;
+        let mut messages = Vec::new();
+        for dir in &dirs {
+            let trash = Path::new(&dir.path);
+            let policy = PathPolicy::new(&[trash.to_path_buf()]);
+            for item in &dir.items {
+                if cutoff.is_some_and(|c| item.deleted_at == 0 || item.deleted_at >= c) {
+                    continue;
+                }
+                match delete_item(&policy, trash, Path::new(&item.path)) {
+                    Ok(_) => removed += 1,
+                    Err(e) => messages.push(e),
+                }
+            }
+            if cutoff.is_none() {
+                remove_orphaned_info(&policy, trash);
+            }
+            // Cached directory sizes go stale; file managers rebuild it.
+            let _ = policy.remove(&trash.join("directorysizes"));
+        }
+        messages.insert(0, format!("Permanently deleted {removed} trashed items"));
+        Ok(CleanupResult {
+            success: true,
+            space_freed: 0,
+            message: messages.join("; "),
+        })
+    })
+}
+
+fn delete_item(policy: &PathPolicy, trash: &Path, path: &Path) -> Result<(), String> {
+    policy.remove(path)?;
+    if let Some(name) = path.file_name() {
+        let _ = policy.remove(&info_path(trash, &name.to_string_lossy()));
+    }
+    Ok(())
+}
+
+/// `.trashinfo` files whose item is gone.
+fn remove_orphaned_info(policy: &PathPolicy, trash: &Path) {
+    let Ok(entries) = fs::read_dir(trash.join("info")) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(item) = name.strip_suffix(".trashinfo") {
+            if fs::symlink_metadata(trash.join("files").join(item)).is_err() {
+                let _ = policy.remove(&entry.path());
+            }
+        }
+    }
+}
+
+/// Moves `path` into the home trash the way file managers do, so that it
+/// can be restored later. `path` must pass `policy`; items on another
+/// filesystem than the home trash are refused rather than copied.
+pub fn move_to_trash(policy: &PathPolicy, path: &Path) -> Result<PathBuf, String> {
+    let resolved = policy.check(path)?;
+    let trash = home_trash();
+    let files = trash.join("files");
+    for dir in [&files, &trash.join("info")] {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    let base = resolved
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Cannot trash {}", resolved.display()))?;
+
+    // Creating the .trashinfo file exclusively reserves the name.
+    let mut n = 1;
+    let (name, info) = loop {
+        let name = if n == 1 {
+            base.clone()
+        } else {
+            format!("{base}.{n}")
+        };
+        let info = info_path(&trash, &name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info)
+        {
+            Ok(_) if fs::symlink_metadata(files.join(&name)).is_err() => break (name, info),
+            Ok(_) => {
+                let _ = fs::remove_file(&info);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("Failed to create {}: {e}", info.display())),
+        }
+        n += 1;
+    };
+    let content = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(&resolved.to_string_lossy()),
+        Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+    let target = files.join(&name);
+    let moved = fs::write(&info, content)
+        .map_err(|e| format!("Failed to write {}: {e}", info.display()))
+        .and_then(|_| {
+            fs::rename(&resolved, &target)
+                .map_err(|e| format!("Failed to trash {}: {e}", resolved.display()))
+        });
+    if let Err(e) = moved {
+        let _ = fs::remove_file(&info);
+        return Err(e);
+    }
+    Ok(target)
+}
+
+/// Percent-encodes everything but unreserved characters and `/`, as
+/// `.trashinfo` paths require.
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Moves a trashed item back to its original path and returns that path.
+/// The path must be absolute, without `..`, and pass the path safety policy
+/// for the home directory (and the trash's mount for per-mount trash).
+/// Missing parent directories are re-created; an existing file at the
+/// original path is never overwritten.
+pub fn restore_trash_item(path: &str) -> Result<String, String> {
+    let item_path = Path::new(path);
+    let (dir, item) = get_trash_contents()?
+        .into_iter()
+        .find_map(|dir| {
+            let item = dir.items.iter().find(|i| i.path == path).cloned()?;
+            Some((dir, item))
+        })
+        .ok_or_else(|| format!("Not a trashed item: {path}"))?;
+    if item.original_path.is_empty() {
+        return Err(format!("No original path recorded for {path}"));
+    }
+
+    let target = PathBuf::from(&item.original_path);
+    if !target.is_absolute() || target.components().any(|c| !is_plain_component(c)) {
+        return Err(format!("Invalid original path {}", target.display()));
+    }
+    let mut roots = path_safety::user_roots();
+    if !dir.is_home {
+        roots.push(PathBuf::from(&dir.mount_point));
+    }
+    let target = PathPolicy::new(&roots)
+        .check(&target)
+        .map_err(|e| format!("Cannot restore {path}: {e}"))?;
+    if fs::symlink_metadata(&target).is_ok() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    move_no_replace(item_path, &target)
+        .map_err(|e| format!("Failed to restore {}: {e}", target.display()))?;
+    let trash = Path::new(&dir.path);
+    let policy = PathPolicy::new(&[trash.to_path_buf()]);
+    if let Some(name) = item_path.file_name() {
+        let _ = policy.remove(&info_path(trash, &name.to_string_lossy()));
+    }
+    let _ = policy.remove(&trash.join("directorysizes"));
+    Ok(item.original_path)
+}
+
+fn is_plain_component(component: Component) -> bool {
+    matches!(component, Component::RootDir | Component::Normal(_))
+}
+
+/// Renames `from` to `to` unless something appears at `to` meanwhile. Files
+/// are hard-linked first, which fails on an existing target; directories and
+/// filesystems without hard links fall back to a plain rename.
+fn move_no_replace(from: &Path, to: &Path) -> std::io::Result<()> {
+    let is_dir = fs::symlink_metadata(from)?.is_dir();
+    if !is_dir {
+        match fs::hard_link(from, to) {
+            Ok(()) => return fs::remove_file(from),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return Err(e),
+            Err(_) => {}
+        }
+    }
+    if fs::symlink_metadata(to).is_ok() {
+        return Err(std::io::ErrorKind::AlreadyExists.into());
+    }
+    fs::rename(from, to)
+}
@@ -0,0 +1,150 @@
+import { useEffect, useState } from "react";
+import { Card } from "@/components/ui/Card";
+import { Input } from "@/components/ui/Input";
+import { Button } from "@/components/ui/Button";
+import { getPathPolicy, setSetting } from "@/services/tauri-commands";
+import type { PathPolicyInfo } from "@/types";
+import { AlertCircle, Plus, X } from "lucide-react";
+
+const PROTECTED_PATHS_KEY = "protected_paths";
+const ALLOWED_ROOTS_KEY = "allowed_cleanup_roots";
+
+interface PathListProps {
+  title: string;
+  description: string;
+  placeholder: string;
+  paths: string[];
+  onChange: (paths: string[]) => Promise<void>;
+}
+
+function PathList({ title, description, placeholder, paths, onChange }: PathListProps) {
+  const [newPath, setNewPath] = useState("");
+
+  async function handleAdd() {
+    const trimmed = newPath.trim();
+    if (!trimmed || paths.includes(trimmed)) return;
+    await onChange([...paths, trimmed]);
+    setNewPath("");
+  }
+
+  return (
+    <div>
+      <p className="text-sm font-medium text-surface-900 dark:text-surface-100">
+        {title}
+      </p>
+      <p className="mb-2 text-xs text-surface-500 dark:text-surface-400">
+        {description}
+      </p>
+      <div className="space-y-2">
+        {paths.map((path) => (
+          <div
+            key={path}
+            className="flex items-center justify-between rounded-lg border border-surface-200 px-3 py-2 dark:border-surface-700"
+          >
+            <span className="text-sm text-surface-700 dark:text-surface-300">
+              {path}
+            </span>
+            <button
+              onClick={() => onChange(paths.filter((p) => p !== path))}
+              className="flex items-center gap-1 text-xs text-red-500 hover:text-red-600"
+            >
+              <X size={12} />
+              Remove
+            </button>
+          </div>
+        ))}
+      </div>
+      <div className="mt-2 flex items-center gap-2">
+        <Input
+          placeholder={placeholder}
+          value={newPath}
+          onChange={(e) => setNewPath(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") handleAdd();
+          }}
+        />
+        <Button
+          variant="secondary"
+          size="sm"
+          onClick={handleAdd}
+          disabled={!newPath.trim()}
+        >
+          <Plus size={14} />
+          Add
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+export function PathSafetySettings() {
+  const [policy, setPolicy] = useState<PathPolicyInfo | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPathPolicy()
+      .then(setPolicy)
+      .catch((e) => setError(String(e)));
+  }, []);
+
+  async function save(key: string, paths: string[]) {
+    setError(null);
+    try {
+      await setSetting(key, JSON.stringify(paths));
+      setPolicy(await getPathPolicy());
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  if (!policy) {
+    return (
+      <Card>
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          {error ?? "Loading..."}
+        </p>
+      </Card>
+    );
+  }
+
+  return (
+    <Card>
+      <div className="space-y-6">
+        <div>
+          <p className="text-sm font-medium text-surface-900 dark:text-surface-100">
+            Always Protected
+          </p>
+          <p className="text-xs text-surface-500 dark:text-surface-400">
+            Cleanups never delete these locations, anything inside{" "}
+            {policy.protected_trees.join(", ")}, or a filesystem mount point.
+            The contents of {policy.protected_paths.join(", ")} may be cleaned,
+            but not the directories themselves.
+          </p>
+        </div>
+
+        {error && (
+          <div className="flex items-start gap-2 rounded-lg border border-red-200 bg-red-50 px-3 py-2 dark:border-red-800 dark:bg-red-900/20">
+            <AlertCircle size={16} className="mt-0.5 shrink-0 text-red-500" />
+            <p className="text-sm text-red-700 dark:text-red-400">{error}</p>
+          </div>
+        )}
+
+        <PathList
+          title="Protected Paths"
+          description="Extra files and directories that no cleanup may delete, including everything inside them."
+          placeholder="e.g. ~/Projects/keep"
+          paths={policy.user.protected_paths}
+          onChange={(paths) => save(PROTECTED_PATHS_KEY, paths)}
+        />
+
+        <PathList
+          title="Additional Cleanup Roots"
+          description="Directories outside your home folder where project artifacts such as target/ and node_modules/ may be removed."
+          placeholder="e.g. /data/src"
+          paths={policy.user.allowed_roots}
+          onChange={(paths) => save(ALLOWED_ROOTS_KEY, paths)}
+        />
+      </div>
+    </Card>
+  );
+}
@@ -11,8 +11,15 @@ import {
   storeApiKey,
   hasApiKey,
   deleteApiKey,
+  getProviderEndpoint,
+  setProviderEndpoint,
+  listProviderModels,
 } from "@/services/tauri-commands";
-import type { AIProviderDefinition, ProviderStatus } from "@/types";
+import type {
+  AIProviderDefinition,
+  ProviderEndpoint,
+  ProviderStatus,
+} from "@/types";
 import { Card } from "@/components/ui/Card";
 import { Button } from "@/components/ui/Button";
 import { Input } from "@/components/ui/Input";
@@ -53,28 +60,48 @@ export function ProviderConfig() {
   const [savedKeys, setSavedKeys] = useState<Record<string, boolean>>({});
   const [saving, setSaving] = useState<string | null>(null);
   const [saveSuccess, setSaveSuccess] = useState<Record<string, boolean>>({});
+  // Local providers: endpoint settings and the models their server lists
+  const [baseUrls, setBaseUrls] = useState<Record<string, string>>({});
+  const [endpoints, setEndpoints] = useState<Record<string, ProviderEndpoint>>(
+    {},
+  );
+  const [localModels, setLocalModels] = useState<Record<string, string[]>>({});
+  const [listing, setListing] = useState<string | null>(null);
+  const [listError, setListError] = useState<Record<string, string | null>>(
+    {},
+  );
 
-  const PROVIDER_IDS = ["openai", "claude", "gemini", "kimi"];
-
-  // Load saved API keys on mount and update provider connection status
+  // Load saved API keys and local endpoints on mount and update provider
+  // connection status. A local provider is connected once a model is saved.
   useEffect(() => {
     async function loadSavedKeys() {
       const saved: Record<string, boolean> = {};
+      const savedEndpoints: Record<string, ProviderEndpoint> = {};
       const statuses: ProviderStatus[] = [];
-      for (const id of PROVIDER_IDS) {
+      for (const def of staticProviderDefs) {
+        const id = def.id;
         try {
           saved[id] = await hasApiKey(id);
         } catch {
+          // The keyring is optional for local providers
           saved[id] = false;
         }
-        const def = staticProviderDefs.find((p) => p.id === id);
+        if (def.local) {
+          try {
+            savedEndpoints[id] = await getProviderEndpoint(id);
+          } catch {
+            savedEndpoints[id] = { base_url: null, model: null };
+          }
+        }
+        const endpoint = savedEndpoints[id];
         statuses.push({
           id,
-          connected: saved[id],
-          model: def?.default_model ?? "",
+          connected: def.local ? !!endpoint?.model : saved[id],
+          model: (def.local ? endpoint?.model : def.default_model) ?? "",
         });
       }
       setSavedKeys(saved);
+      setEndpoints(savedEndpoints);
       setProviders(statuses);
     }
     loadSavedKeys();
@@ -108,24 +135,70 @@ export function ProviderConfig() {
     return providerStatuses.find((p) => p.id === id);
   }
 
+  function getModel(def: AIProviderDefinition) {
+    if (def.local) {
+      return selectedModels[def.id] ?? endpoints[def.id]?.model ?? "";
+    }
+    return selectedModels[def.id] || def.default_model;
+  }
+
+  function getBaseUrl(def: AIProviderDefinition) {
+    return baseUrls[def.id] ?? endpoints[def.id]?.base_url ?? "";
+  }
+
+  async function handleListModels(def: AIProviderDefinition) {
+    setListing(def.id);
+    setListError((prev) => ({ ...prev, [def.id]: null }));
+    try {
+      const models = await listProviderModels(
+        def.id,
+        getBaseUrl(def) || null,
+        apiKeys[def.id] || null,
+      );
+      setLocalModels((prev) => ({ ...prev, [def.id]: models }));
+      if (!getModel(def) && models.length > 0) {
+        setSelectedModels((prev) => ({ ...prev, [def.id]: models[0] }));
+      }
+    } catch (e) {
+      setListError((prev) => ({ ...prev, [def.id]: String(e) }));
+    } finally {
+      setListing(null);
+    }
+  }
+
+  async function saveEndpoint(def: AIProviderDefinition, model: string) {
+    const endpoint = { base_url: getBaseUrl(def) || null, model };
+    await setProviderEndpoint(def.id, endpoint);
+    setEndpoints((prev) => ({ ...prev, [def.id]: endpoint }));
+  }
+
   async function handleTest(providerId: string) {
     const key = apiKeys[providerId] || null;
-    // Allow testing if user typed a key OR if a key is saved in keyring
-    if (!key && !savedKeys[providerId]) return;
     const def = providerDefs.find((p) => p.id === providerId);
-    const model = selectedModels[providerId] || def?.default_model || "";
+    if (!def) return;
+    // Allow testing if user typed a key OR if a key is saved in keyring;
+    // local providers may need no key at all
+    if (!def.local && !key && !savedKeys[providerId]) return;
+    const model = getModel(def);
+    if (!model) return;
 
     setTesting(providerId);
     setTestResult((prev) => ({ ...prev, [providerId]: null }));
     try {
       // Pass key (or null to let backend read from keyring)
-      const ok = await testAiConnection(providerId, key, model);
+      const ok = await testAiConnection(
+        providerId,
+        key,
+        model,
+        def.local ? getBaseUrl(def) || null : null,
+      );
       setTestResult((prev) => ({ ...prev, [providerId]: ok }));
       if (ok) {
         if (key) {
           await storeApiKey(providerId, key);
           setSavedKeys((prev) => ({ ...prev, [providerId]: true }));
         }
+        if (def.local) await saveEndpoint(def, model);
         // Update provider connected status in global store
         setProviders(
           providerStatuses.map((p) =>
@@ -142,11 +215,23 @@ export function ProviderConfig() {
 
   async function handleSave(providerId: string) {
     const key = apiKeys[providerId];
-    if (!key) return;
+    const def = providerDefs.find((p) => p.id === providerId);
+    if (!def || (!key && !def.local)) return;
     setSaving(providerId);
     try {
-      await storeApiKey(providerId, key);
-      setSavedKeys((prev) => ({ ...prev, [providerId]: true }));
+      if (def.local) {
+        const model = getModel(def);
+        await saveEndpoint(def, model);
+        setProviders(
+          providerStatuses.map((p) =>
+            p.id === providerId ? { ...p, connected: !!model, model } : p,
+          ),
+        );
+      }
+      if (key) {
+        await storeApiKey(providerId, key);
+        setSavedKeys((prev) => ({ ...prev, [providerId]: true }));
+      }
       setSaveSuccess((prev) => ({ ...prev, [providerId]: true }));
       setTimeout(() => {
         setSaveSuccess((prev) => ({ ...prev, [providerId]: false }));
@@ -159,8 +244,28 @@ export function ProviderConfig() {
   }
 
   async function handleRemove(providerId: string) {
+    const def = providerDefs.find((p) => p.id === providerId);
     try {
-      await deleteApiKey(providerId);
+      if (def?.local) {
+        await setProviderEndpoint(providerId, { base_url: null, model: null });
+        setEndpoints((prev) => ({
+          ...prev,
+          [providerId]: { base_url: null, model: null },
+        }));
+        setSelectedModels((prev) => {
+          const next = { ...prev };
+          delete next[providerId];
+          return next;
+        });
+        setBaseUrls((prev) => {
+          const next = { ...prev };
+          delete next[providerId];
+          return next;
+        });
+        if (savedKeys[providerId]) await deleteApiKey(providerId);
+      } else {
+        await deleteApiKey(providerId);
+      }
       setApiKeys((prev) => {
         const next = { ...prev };
         delete next[providerId];
@@ -205,7 +310,8 @@ export function ProviderConfig() {
         const status = getStatus(def.id);
         const isExpanded = expandedId === def.id;
         const isActive = activeProvider === def.id;
-        const model = selectedModels[def.id] || def.default_model;
+        const model = getModel(def);
+        const served = localModels[def.id] ?? [];
 
         return (
           <Card key={def.id} padding="none" hover>
@@ -227,10 +333,16 @@ export function ProviderConfig() {
                       Active
                     </Badge>
                   )}
-                  <Badge variant="default" size="sm">
-                    {def.models.length} model
-                    {def.models.length !== 1 ? "s" : ""}
-                  </Badge>
+                  {def.local ? (
+                    <Badge variant="default" size="sm">
+                      Local
+                    </Badge>
+                  ) : (
+                    <Badge variant="default" size="sm">
+                      {def.models.length} model
+                      {def.models.length !== 1 ? "s" : ""}
+                    </Badge>
+                  )}
                 </div>
                 <p className="text-xs text-surface-500 dark:text-surface-400">
                   {def.description}
@@ -255,10 +367,44 @@ export function ProviderConfig() {
             {isExpanded && (
               <div className="border-t border-surface-200 px-4 py-4 dark:border-surface-700">
                 <div className="space-y-4">
+                  {def.local && (
+                    <div>
+                      <div className="flex items-end gap-2">
+                        <div className="flex-1">
+                          <Input
+                            label="Base URL"
+                            placeholder={def.default_base_url}
+                            value={getBaseUrl(def)}
+                            onChange={(e) =>
+                              setBaseUrls((prev) => ({
+                                ...prev,
+                                [def.id]: e.target.value,
+                              }))
+                            }
+                          />
+                        </div>
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          loading={listing === def.id}
+                          iconLeft={<RefreshCw size={14} />}
+                          onClick={() => handleListModels(def)}
+                        >
+                          Load Models
+                        </Button>
+                      </div>
+                      {listError[def.id] && (
+                        <p className="mt-1 text-xs text-red-600 dark:text-red-400">
+                          {listError[def.id]}
+                        </p>
+                      )}
+                    </div>
+                  )}
+
                   <div>
                     <Input
                       variant="password"
-                      label="API Key"
+                      label={def.auth_methods[0]?.label || "API Key"}
                       placeholder={
                         savedKeys[def.id]
                           ? "\u2022\u2022\u2022\u2022\u2022\u2022\u2022\u2022 (saved)"
@@ -288,26 +434,59 @@ export function ProviderConfig() {
                     <label className="mb-1.5 block text-sm font-medium text-surface-700 dark:text-surface-300">
                       Model
                     </label>
-                    <select
-                      value={model}
-                      onChange={(e) =>
-                        setSelectedModels((prev) => ({
-                          ...prev,
-                          [def.id]: e.target.value,
-                        }))
-                      }
-                      className="w-full rounded-lg border border-surface-300 bg-white px-3 py-2 text-sm text-surface-700 dark:border-surface-600 dark:bg-surface-800 dark:text-surface-300"
-                    >
-                      {def.models.map((m) => (
-                        <option key={m.id} value={m.id}>
-                          {m.name} ({formatContextLength(m.max_tokens)} ctx
-                          {m.cost_per_1k_input != null
-                            ? ` | $${m.cost_per_1k_input.toFixed(4)}/1K in`
-                            : ""}
-                          )
-                        </option>
-                      ))}
-                    </select>
+                    {def.local && served.length === 0 ? (
+                      <Input
+                        placeholder="Load models or enter a model name"
+                        value={model}
+                        onChange={(e) =>
+                          setSelectedModels((prev) => ({
+                            ...prev,
+                            [def.id]: e.target.value,
+                          }))
+                        }
+                      />
+                    ) : def.local ? (
+                      <select
+                        value={model}
+                        onChange={(e) =>
+                          setSelectedModels((prev) => ({
+                            ...prev,
+                            [def.id]: e.target.value,
+                          }))
+                        }
+                        className="w-full rounded-lg border border-surface-300 bg-white px-3 py-2 text-sm text-surface-700 dark:border-surface-600 dark:bg-surface-800 dark:text-surface-300"
+                      >
+                        {model && !served.includes(model) && (
+                          <option value={model}>{model}</option>
+                        )}
+                        {served.map((m) => (
+                          <option key={m} value={m}>
+                            {m}
+                          </option>
+                        ))}
+                      </select>
+                    ) : (
+                      <select
+                        value={model}
+                        onChange={(e) =>
+                          setSelectedModels((prev) => ({
+                            ...prev,
+                            [def.id]: e.target.value,
+                          }))
+                        }
+                        className="w-full rounded-lg border border-surface-300 bg-white px-3 py-2 text-sm text-surface-700 dark:border-surface-600 dark:bg-surface-800 dark:text-surface-300"
+                      >
+                        {def.models.map((m) => (
+                          <option key={m.id} value={m.id}>
+                            {m.name} ({formatContextLength(m.max_tokens)} ctx
+                            {m.cost_per_1k_input != null
+                              ? ` | $${m.cost_per_1k_input.toFixed(4)}/1K in`
+                              : ""}
+                            )
+                          </option>
+                        ))}
+                      </select>
+                    )}
                   </div>
 
                   {/* Selected model details */}
@@ -370,16 +549,20 @@ export function ProviderConfig() {
                       loading={saving === def.id}
                       iconLeft={<Save size={14} />}
                       onClick={() => handleSave(def.id)}
-                      disabled={!apiKeys[def.id]}
+                      disabled={def.local ? !model : !apiKeys[def.id]}
                     >
-                      Save Key
+                      {def.local ? "Save" : "Save Key"}
                     </Button>
                     <Button
                       variant="outline"
                       size="sm"
                       loading={testing === def.id}
                       onClick={() => handleTest(def.id)}
-                      disabled={!apiKeys[def.id] && !savedKeys[def.id]}
+                      disabled={
+                        def.local
+                          ? !model
+                          : !apiKeys[def.id] && !savedKeys[def.id]
+                      }
                     >
                       Test Connection
                     </Button>
@@ -409,7 +592,7 @@ export function ProviderConfig() {
                         rel="noopener noreferrer"
                         className="ml-auto inline-flex items-center gap-1 text-xs text-primary-500 hover:text-primary-600"
                       >
-                        Get API Key
+                        {def.local ? "Documentation" : "Get API Key"}
                         <ExternalLink size={12} />
                       </a>
                     )}
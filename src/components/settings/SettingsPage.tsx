@@ -5,12 +5,13 @@ import { Button } from "@/components/ui/Button";
 import { ThemeToggle } from "./ThemeToggle";
 import { ProviderConfig } from "./ProviderConfig";
 import { UpdateChecker } from "./UpdateChecker";
+import { PathSafetySettings } from "./PathSafetySettings";
 import {
   getCurrentVersion,
   getSetting,
   setSetting,
 } from "@/services/tauri-commands";
-import { Palette, Bot, Download, Info, Plus, X } from "lucide-react";
+import { Palette, Bot, Download, Info, Plus, ShieldCheck, X } from "lucide-react";
 
 const DEFAULT_DIRECTORIES = ["~/Documents", "~/Downloads", "~/Desktop"];
 const SCAN_DIRS_KEY = "scan_directories";
@@ -175,6 +176,17 @@ export function SettingsPage() {
         </Card>
       </section>
 
+      {/* Deletion Safety */}
+      <section>
+        <div className="mb-4 flex items-center gap-2">
+          <ShieldCheck size={18} className="text-surface-400" />
+          <h2 className="text-lg font-semibold text-surface-900 dark:text-surface-50">
+            Deletion Safety
+          </h2>
+        </div>
+        <PathSafetySettings />
+      </section>
+
       {/* AI Providers */}
       <section>
         <div className="mb-4 flex items-center gap-2">
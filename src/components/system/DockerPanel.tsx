@@ -2,10 +2,15 @@ import { useState, useEffect } from "react";
 import { cn } from "@/lib/utils";
 import { formatBytes } from "@/lib/utils";
 import { useAppStore } from "@/stores/app-store";
-import { cleanDocker, getDockerInfo } from "@/services/tauri-commands";
+import {
+  cleanDocker,
+  getDockerInfo,
+  teardownComposeProject,
+} from "@/services/tauri-commands";
 import { Card } from "@/components/ui/Card";
 import { Button } from "@/components/ui/Button";
 import { Badge } from "@/components/ui/Badge";
+import type { ImageUsage } from "@/types";
 import {
   Box,
   Container,
@@ -14,9 +19,20 @@ import {
   RefreshCw,
   Loader2,
   AlertCircle,
+  Layers,
 } from "lucide-react";
 
-type DockerTab = "images" | "containers" | "volumes";
+type DockerTab = "images" | "containers" | "volumes" | "projects";
+
+const IMAGE_USAGE_BADGE: Record<
+  ImageUsage,
+  { label: string; variant: "success" | "warning" | "outline" }
+> = {
+  running: { label: "In Use", variant: "success" },
+  stopped_only: { label: "Stopped Only", variant: "warning" },
+  unused: { label: "Unused", variant: "outline" },
+  dangling: { label: "Dangling", variant: "outline" },
+};
 
 interface DockerPanelProps {
   onRefresh?: () => Promise<void>;
@@ -107,6 +123,7 @@ export function DockerPanel({ onRefresh }: DockerPanelProps) {
     { id: "images", label: "Images", icon: <Box size={16} />, count: dockerInfo.images.length },
     { id: "containers", label: "Containers", icon: <Container size={16} />, count: dockerInfo.containers.length },
     { id: "volumes", label: "Volumes", icon: <Database size={16} />, count: dockerInfo.volumes.length },
+    { id: "projects", label: "Compose", icon: <Layers size={16} />, count: dockerInfo.compose_projects.length },
   ];
 
   const toggleId = (id: string) => {
@@ -143,6 +160,33 @@ export function DockerPanel({ onRefresh }: DockerPanelProps) {
     }
   }
 
+  async function handleTeardown(project: string, force: boolean) {
+    setCleaning(true);
+    setError(null);
+    setCleanResult(null);
+    try {
+      const result = await teardownComposeProject(
+        project,
+        dockerInfo?.runtime,
+        force,
+      );
+      if (result.success) {
+        setCleanResult(result.message);
+      } else {
+        setError(result.message);
+      }
+      if (onRefresh) {
+        await onRefresh();
+      } else {
+        await loadDockerInfo();
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Teardown failed.");
+    } finally {
+      setCleaning(false);
+    }
+  }
+
   const totalImageSize = dockerInfo.images.reduce((s, i) => s + i.size, 0);
   const totalVolumeSize = dockerInfo.volumes.reduce((s, v) => s + v.size, 0);
 
@@ -211,14 +255,16 @@ export function DockerPanel({ onRefresh }: DockerPanelProps) {
               Clean Selected ({selectedIds.size})
             </Button>
           )}
-          <Button
-            variant="outline"
-            size="sm"
-            loading={cleaning}
-            onClick={() => handleClean(activeTab)}
-          >
-            Clean All Unused
-          </Button>
+          {activeTab !== "projects" && (
+            <Button
+              variant="outline"
+              size="sm"
+              loading={cleaning}
+              onClick={() => handleClean(activeTab)}
+            >
+              Clean All Unused
+            </Button>
+          )}
         </div>
       </div>
 
@@ -261,8 +307,8 @@ export function DockerPanel({ onRefresh }: DockerPanelProps) {
                 <p className="text-xs text-surface-400">{img.created}</p>
               </label>
               <span className="text-sm text-surface-500">{formatBytes(img.size)}</span>
-              <Badge variant={img.in_use ? "success" : "outline"} size="sm">
-                {img.in_use ? "In Use" : "Unused"}
+              <Badge variant={IMAGE_USAGE_BADGE[img.usage].variant} size="sm">
+                {IMAGE_USAGE_BADGE[img.usage].label}
               </Badge>
             </div>
           ))}
@@ -323,6 +369,34 @@ export function DockerPanel({ onRefresh }: DockerPanelProps) {
               </Badge>
             </div>
           ))}
+
+        {activeTab === "projects" &&
+          dockerInfo.compose_projects.map((project) => (
+            <div key={`proj-${project.name}`} className="flex items-center gap-3 px-4 py-2.5">
+              <div className="min-w-0 flex-1">
+                <p className="truncate text-sm font-medium text-surface-900 dark:text-surface-100">
+                  {project.name}
+                </p>
+                <p className="truncate text-xs text-surface-400">
+                  {project.working_dir ?? "Unknown directory"} · {project.containers.length}{" "}
+                  containers, {project.volumes.length} volumes, {project.networks.length} networks
+                </p>
+              </div>
+              <span className="text-sm text-surface-500">{formatBytes(project.size)}</span>
+              <Badge variant={project.running ? "success" : "outline"} size="sm">
+                {project.running ? "Running" : "Stopped"}
+              </Badge>
+              <Button
+                variant="danger"
+                size="sm"
+                iconLeft={<Trash2 size={14} />}
+                loading={cleaning}
+                onClick={() => handleTeardown(project.name, project.running)}
+              >
+                {project.running ? "Stop & Tear Down" : "Tear Down"}
+              </Button>
+            </div>
+          ))}
       </div>
     </Card>
   );
@@ -72,10 +72,7 @@ export function SystemCleanup() {
     refreshPackageCaches();
   }, [refreshDockerInfo, refreshPackageCaches]);
 
-  const dockerSpace =
-    (dockerInfo?.build_cache_size ?? 0) +
-    (dockerInfo?.images.filter((i) => !i.in_use).reduce((s, i) => s + i.size, 0) ?? 0) +
-    (dockerInfo?.volumes.filter((v) => !v.in_use).reduce((s, v) => s + v.size, 0) ?? 0);
+  const dockerSpace = dockerInfo?.total_reclaimable ?? 0;
   const cacheSpace = packageCaches.reduce((s, c) => s + c.size, 0);
   const totalReclaimable =
     cleanupRecommendations.reduce((s, r) => s + r.space_reclaimable, 0) +
@@ -4,6 +4,8 @@
  * Provider metadata (auth, capabilities) is static.
  * Model lists can be populated dynamically from the OpenRouter API
  * via the model registry, falling back to static defaults when offline.
+ * Local providers have no static models; theirs are listed from the
+ * configured server.
  */
 
 import type { AIProviderDefinition, ModelDefinition } from "@/types";
@@ -218,6 +220,81 @@ const PROVIDER_META: Omit<AIProviderDefinition, "models">[] = [
       tools: false,
     },
   },
+  {
+    id: "ollama",
+    name: "Ollama",
+    description:
+      "Models served by Ollama on this machine or your network. File paths never leave it.",
+    auth_methods: [
+      {
+        type: "token",
+        label: "API Key (optional)",
+        description: "Only needed behind an authenticating proxy",
+        key_placeholder: "Leave empty for no authentication",
+        help_url: "https://ollama.com/library",
+      },
+    ],
+    default_model: "",
+    local: true,
+    default_base_url: "http://localhost:11434",
+    capabilities: {
+      chat: true,
+      vision: false,
+      json: true,
+      streaming: false,
+      tools: false,
+    },
+  },
+  {
+    id: "llamacpp",
+    name: "llama.cpp Server",
+    description:
+      "A local llama-server through its OpenAI-compatible API. File paths never leave your network.",
+    auth_methods: [
+      {
+        type: "token",
+        label: "API Key (optional)",
+        description: "The --api-key the server was started with",
+        key_placeholder: "Leave empty for no authentication",
+        help_url:
+          "https://github.com/ggml-org/llama.cpp/tree/master/tools/server",
+      },
+    ],
+    default_model: "",
+    local: true,
+    default_base_url: "http://localhost:8080/v1",
+    capabilities: {
+      chat: true,
+      vision: false,
+      json: true,
+      streaming: false,
+      tools: false,
+    },
+  },
+  {
+    id: "openai-compatible",
+    name: "OpenAI-Compatible Server",
+    description:
+      "Any self-hosted OpenAI-compatible endpoint, such as vLLM or LM Studio.",
+    auth_methods: [
+      {
+        type: "token",
+        label: "API Key (optional)",
+        description: "Only if the server requires one",
+        key_placeholder: "Leave empty for no authentication",
+      },
+    ],
+    default_model: "",
+    local: true,
+    default_base_url: "http://localhost:8000/v1",
+    capabilities: {
+      chat: true,
+      vision: false,
+      json: true,
+      streaming: false,
+      tools: false,
+    },
+  },
 ];
 
 /**
@@ -3,11 +3,28 @@ import type {
   FileInfo,
   SystemInfo,
   StorageBreakdown,
+  ContainerRuntime,
   DockerInfo,
+  PrunePolicy,
   CleanupResult,
   PackageCacheInfo,
   AIAnalysis,
+  CleanupRecommendation,
   UpdateInfo,
+  CargoCacheSummary,
+  CargoTargetDir,
+  ProjectArtifact,
+  BrowserDataCategory,
+  BrowserCategorySize,
+  BrowserRunningPolicy,
+  BrowserProfileInfo,
+  CacheEntry,
+  TempLocation,
+  TrashDir,
+  SandboxedAppsReport,
+  OrphanReport,
+  PathPolicyInfo,
+  ProviderEndpoint,
 } from "@/types";
 
 // File operations
@@ -32,12 +49,40 @@ export async function getStorageBreakdown(): Promise<StorageBreakdown> {
   return invoke<StorageBreakdown>("get_storage_breakdown");
 }
 
-export async function getDockerInfo(): Promise<DockerInfo> {
-  return invoke<DockerInfo>("get_docker_info");
+export async function getContainerRuntimes(): Promise<ContainerRuntime[]> {
+  return invoke<ContainerRuntime[]>("get_container_runtimes");
 }
 
-export async function cleanDocker(target: string, ids?: string[]): Promise<CleanupResult> {
-  return invoke<CleanupResult>("clean_docker", { target, ids });
+export async function getDockerInfo(runtime?: ContainerRuntime): Promise<DockerInfo> {
+  return invoke<DockerInfo>("get_docker_info", { runtime });
+}
+
+export async function cleanDocker(
+  target: string,
+  ids?: string[],
+  runtime?: ContainerRuntime,
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_docker", { target, ids, runtime });
+}
+
+export async function pruneDocker(
+  target: string,
+  policy: PrunePolicy,
+  runtime?: ContainerRuntime,
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("prune_docker", { target, policy, runtime });
+}
+
+export async function teardownComposeProject(
+  project: string,
+  runtime?: ContainerRuntime,
+  force = false,
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("teardown_compose_project", {
+    project,
+    force,
+    runtime,
+  });
 }
 
 export async function getPackageCaches(): Promise<PackageCacheInfo[]> {
@@ -50,11 +95,42 @@ export async function cleanPackageCache(
   return invoke<CleanupResult>("clean_package_cache", { manager });
 }
 
+export async function previewPackageCacheCleanup(
+  manager: string,
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("preview_package_cache_cleanup", { manager });
+}
+
 // Model discovery
 export async function fetchAvailableModels(): Promise<string> {
   return invoke<string>("fetch_available_models");
 }
 
+export async function listProviderModels(
+  provider: string,
+  baseUrl?: string | null,
+  apiKey?: string | null,
+): Promise<string[]> {
+  return invoke<string[]>("list_provider_models", {
+    provider,
+    baseUrl,
+    apiKey,
+  });
+}
+
+export async function getProviderEndpoint(
+  provider: string,
+): Promise<ProviderEndpoint> {
+  return invoke<ProviderEndpoint>("get_provider_endpoint", { provider });
+}
+
+export async function setProviderEndpoint(
+  provider: string,
+  endpoint: ProviderEndpoint,
+): Promise<void> {
+  return invoke<void>("set_provider_endpoint", { provider, endpoint });
+}
+
 // AI operations
 export async function chatWithAi(
   provider: string,
@@ -68,8 +144,14 @@ export async function testAiConnection(
   provider: string,
   apiKey: string | null,
   model: string,
+  baseUrl?: string | null,
 ): Promise<boolean> {
-  return invoke<boolean>("test_ai_connection", { provider, apiKey, model });
+  return invoke<boolean>("test_ai_connection", {
+    provider,
+    apiKey,
+    model,
+    baseUrl,
+  });
 }
 
 export async function analyzeFilesWithAi(
@@ -82,6 +164,25 @@ export async function analyzeFilesWithAi(
   });
 }
 
+export async function classifyFiles(filePaths: string[]): Promise<AIAnalysis[]> {
+  return invoke<AIAnalysis[]>("classify_files", { filePaths });
+}
+
+export async function getCleanupRecommendations(
+  provider: string,
+): Promise<CleanupRecommendation[]> {
+  return invoke<CleanupRecommendation[]>("get_cleanup_recommendations", {
+    provider,
+  });
+}
+
+export async function applyRecommendation(
+  id: string,
+  selectedItems: string[],
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("apply_recommendation", { id, selectedItems });
+}
+
 // Credential operations
 export async function storeApiKey(
   provider: string,
@@ -116,6 +217,31 @@ export async function cleanLogs(): Promise<CleanupResult> {
   return invoke<CleanupResult>("clean_logs");
 }
 
+// Temp file operations
+export async function getTempUsage(days?: number): Promise<TempLocation[]> {
+  return invoke<TempLocation[]>("get_temp_usage", { days });
+}
+
+export async function cleanTempFiles(
+  days?: number,
+  privileged?: boolean,
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_temp_files", { days, privileged });
+}
+
+// Trash operations
+export async function getTrashContents(): Promise<TrashDir[]> {
+  return invoke<TrashDir[]>("get_trash_contents");
+}
+
+export async function emptyTrash(olderThanDays?: number): Promise<CleanupResult> {
+  return invoke<CleanupResult>("empty_trash", { olderThanDays });
+}
+
+export async function restoreTrashItem(path: string): Promise<string> {
+  return invoke<string>("restore_trash_item", { path });
+}
+
 // Browser cache operations
 export async function getBrowserCaches(): Promise<PackageCacheInfo[]> {
   return invoke<PackageCacheInfo[]>("get_browser_caches");
@@ -123,8 +249,141 @@ export async function getBrowserCaches(): Promise<PackageCacheInfo[]> {
 
 export async function cleanBrowserCache(
   browser: string,
+  categories?: BrowserDataCategory[],
+  whenRunning?: BrowserRunningPolicy,
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_browser_cache", {
+    browser,
+    categories,
+    whenRunning,
+  });
+}
+
+export async function getBrowserProfiles(): Promise<BrowserProfileInfo[]> {
+  return invoke<BrowserProfileInfo[]>("get_browser_profiles");
+}
+
+export async function cleanBrowserProfile(
+  browser: string,
+  profileId: string,
+  categories?: BrowserDataCategory[],
+  whenRunning?: BrowserRunningPolicy,
 ): Promise<CleanupResult> {
-  return invoke<CleanupResult>("clean_browser_cache", { browser });
+  return invoke<CleanupResult>("clean_browser_profile", {
+    browser,
+    profileId,
+    categories,
+    whenRunning,
+  });
+}
+
+export async function getRunningBrowsers(): Promise<string[]> {
+  return invoke<string[]>("get_running_browsers");
+}
+
+export async function cancelQueuedBrowserCleanup(
+  browser: string,
+  profileId?: string,
+): Promise<void> {
+  return invoke<void>("cancel_queued_browser_cleanup", { browser, profileId });
+}
+
+export async function getBrowserDataCategories(
+  browser: string,
+): Promise<BrowserCategorySize[]> {
+  return invoke<BrowserCategorySize[]>("get_browser_data_categories", {
+    browser,
+  });
+}
+
+// ~/.cache audit operations
+export async function auditCacheDir(): Promise<CacheEntry[]> {
+  return invoke<CacheEntry[]>("audit_cache_dir");
+}
+
+export async function cleanCacheEntries(names: string[]): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_cache_entries", { names });
+}
+
+export async function cleanStaleCacheEntries(
+  days: number,
+  includeCaution?: boolean,
+  includeUnknown?: boolean,
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_stale_cache_entries", {
+    days,
+    includeCaution,
+    includeUnknown,
+  });
+}
+
+// Flatpak / Snap operations
+export async function getSandboxedApps(): Promise<SandboxedAppsReport> {
+  return invoke<SandboxedAppsReport>("get_sandboxed_apps");
+}
+
+export async function removeUnusedFlatpakRuntimes(): Promise<CleanupResult> {
+  return invoke<CleanupResult>("remove_unused_flatpak_runtimes");
+}
+
+export async function removeDisabledSnapRevisions(): Promise<CleanupResult> {
+  return invoke<CleanupResult>("remove_disabled_snap_revisions");
+}
+
+export async function cleanOrphanedAppData(appIds: string[]): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_orphaned_app_data", { appIds });
+}
+
+// Orphan operations
+export async function findOrphans(): Promise<OrphanReport> {
+  return invoke<OrphanReport>("find_orphans");
+}
+
+// Cargo operations
+export async function getCargoCacheSummary(): Promise<CargoCacheSummary> {
+  return invoke<CargoCacheSummary>("get_cargo_cache_summary");
+}
+
+export async function findCargoTargetDirs(
+  roots?: string[],
+): Promise<CargoTargetDir[]> {
+  return invoke<CargoTargetDir[]>("find_cargo_target_dirs", { roots });
+}
+
+export async function cleanCargoTargetDirs(
+  paths: string[],
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_cargo_target_dirs", { paths });
+}
+
+export async function pruneCargoRegistry(keep?: number): Promise<CleanupResult> {
+  return invoke<CleanupResult>("prune_cargo_registry", { keep });
+}
+
+export async function cleanCargoGitCheckouts(): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_cargo_git_checkouts");
+}
+
+// Project artifact operations
+export async function findProjectArtifacts(
+  root: string,
+  kinds?: string[],
+): Promise<ProjectArtifact[]> {
+  return invoke<ProjectArtifact[]>("find_project_artifacts", { root, kinds });
+}
+
+export async function cleanProjectArtifacts(
+  paths: string[],
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("clean_project_artifacts", { paths });
+}
+
+export async function sweepInactiveProjects(
+  root: string,
+  days: number,
+  kinds?: string[],
+): Promise<CleanupResult> {
+  return invoke<CleanupResult>("sweep_inactive_projects", { root, days, kinds });
 }
 
 // Settings
@@ -136,6 +395,10 @@ export async function setSetting(key: string, value: string): Promise<void> {
   return invoke<void>("set_setting", { key, value });
 }
 
+export async function getPathPolicy(): Promise<PathPolicyInfo> {
+  return invoke<PathPolicyInfo>("get_path_policy");
+}
+
 // Update operations
 export async function checkForUpdates(): Promise<UpdateInfo | null> {
   return invoke<UpdateInfo | null>("check_for_updates");
@@ -79,13 +79,43 @@ export interface StorageCategory {
 }
 
 // Docker types
+export type ContainerRuntime = "docker" | "podman" | "nerdctl";
+
 export interface DockerInfo {
+  runtime: ContainerRuntime;
   images: DockerImage[];
   containers: DockerContainer[];
   volumes: DockerVolume[];
+  compose_projects: ComposeProject[];
+  disk_usage: DiskUsageCategory[];
+  build_cache: BuildCacheRecord[];
   build_cache_size: number;
+  total_reclaimable: number;
+}
+
+export type UsageCategory = "images" | "containers" | "volumes" | "build_cache";
+
+export interface DiskUsageCategory {
+  category: UsageCategory;
+  total_count: number;
+  active_count: number;
+  size: number;
+  reclaimable: number;
+}
+
+export interface BuildCacheRecord {
+  id: string;
+  kind: string;
+  description: string;
+  size: number;
+  shared: boolean;
+  in_use: boolean;
+  last_used?: string;
+  usage_count: number;
 }
 
+export type ImageUsage = "running" | "stopped_only" | "unused" | "dangling";
+
 export interface DockerImage {
   id: string;
   repository: string;
@@ -93,6 +123,7 @@ export interface DockerImage {
   size: number;
   created: string;
   in_use: boolean;
+  usage: ImageUsage;
 }
 
 export interface DockerContainer {
@@ -111,6 +142,27 @@ export interface DockerVolume {
   in_use: boolean;
 }
 
+export interface ComposeProject {
+  name: string;
+  working_dir?: string;
+  running: boolean;
+  containers: string[];
+  images: string[];
+  volumes: string[];
+  networks: string[];
+  size: number;
+}
+
+export interface PrunePolicy {
+  older_than_hours?: number;
+  include_labels?: string[];
+  exclude_labels?: string[];
+  keep_recent_tags?: number;
+  compose_dirs?: string[];
+  /** Also prune unused named volumes (Podman always does) */
+  include_named_volumes?: boolean;
+}
+
 // Package cache types
 export interface PackageCacheInfo {
   manager: string;
@@ -119,6 +171,161 @@ export interface PackageCacheInfo {
   exists: boolean;
 }
 
+// Temp file types
+export interface TempLocation {
+  path: string;
+  size: number;
+  file_count: number;
+  reclaimable: number;
+  reclaimable_count: number;
+}
+
+// Trash types
+export interface TrashItem {
+  path: string;
+  original_path: string;
+  deletion_date: string;
+  deleted_at: number;
+  size: number;
+  is_dir: boolean;
+}
+
+export interface TrashDir {
+  path: string;
+  mount_point: string;
+  is_home: boolean;
+  size: number;
+  items: TrashItem[];
+}
+
+// Browser types
+export type BrowserDataCategory =
+  | "cache"
+  | "code_cache"
+  | "gpu_cache"
+  | "service_worker"
+  | "shader_cache"
+  | "startup_cache"
+  | "thumbnails"
+  | "crash_reports"
+  | "crashpad"
+  | "site_data";
+
+export type BrowserRunningPolicy = "refuse" | "warn" | "queue";
+
+export interface BrowserCategorySize {
+  category: BrowserDataCategory;
+  size: number;
+  default_selected: boolean;
+}
+
+export interface BrowserProfileInfo {
+  browser: string;
+  profile_id: string;
+  name: string;
+  path: string;
+  categories: BrowserCategorySize[];
+  total_size: number;
+  running: boolean;
+}
+
+// ~/.cache audit types
+export type CacheSafety = "safe" | "caution" | "unknown";
+
+export interface CacheEntry {
+  name: string;
+  path: string;
+  size: number;
+  modified_at: number;
+  description?: string;
+  safety: CacheSafety;
+}
+
+// Flatpak / Snap types
+export interface FlatpakRef {
+  ref_id: string;
+  id: string;
+  kind: "app" | "runtime";
+  /** "user", "system" or the name of a custom installation */
+  installation: string;
+  path: string;
+  size: number;
+  unused: boolean;
+}
+
+export interface SnapRevision {
+  name: string;
+  version: string;
+  revision: string;
+  disabled: boolean;
+  path: string;
+  size: number;
+}
+
+export interface AppDataDir {
+  app_id: string;
+  platform: "flatpak" | "snap";
+  path: string;
+  size: number;
+  installed: boolean;
+}
+
+export interface SandboxedAppsReport {
+  flatpak_refs: FlatpakRef[];
+  snap_revisions: SnapRevision[];
+  app_data: AppDataDir[];
+}
+
+// Orphan types
+export interface OrphanPackage {
+  name: string;
+  size: number;
+}
+
+export type LeftoverConfidence = "likely" | "ambiguous";
+
+export interface LeftoverDir {
+  name: string;
+  path: string;
+  location: "config" | "data";
+  size: number;
+  confidence: LeftoverConfidence;
+  similar_to: string[];
+}
+
+export interface OrphanReport {
+  package_manager?: string;
+  packages: OrphanPackage[];
+  leftovers: LeftoverDir[];
+}
+
+// Cargo types
+export interface CargoCacheSummary {
+  cargo_home: string;
+  registry_index: number;
+  registry_cache: number;
+  registry_src: number;
+  git_db: number;
+  git_checkouts: number;
+}
+
+export interface CargoTargetDir {
+  path: string;
+  project: string;
+  size: number;
+  last_build: number;
+}
+
+// Project artifact types
+export interface ProjectArtifact {
+  path: string;
+  project: string;
+  kind: string;
+  size: number;
+  /** Null when the project could not be fully read. */
+  last_touched: number | null;
+}
+
 // Cleanup types
 export interface CleanupRecommendation {
   id: string;
@@ -131,6 +338,7 @@ export interface CleanupRecommendation {
 }
 
 export interface CleanupItem {
+  candidate_id: string;
   path: string;
   size: number;
   description: string;
@@ -158,6 +366,14 @@ export interface AIProviderDefinition {
   default_model: string;
   models: ModelDefinition[];
   capabilities: ProviderCapabilities;
+  /** Runs on a server the user controls; base URL is configurable, API key optional */
+  local?: boolean;
+  default_base_url?: string;
+}
+
+export interface ProviderEndpoint {
+  base_url: string | null;
+  model: string | null;
 }
 
 export interface AuthMethod {
@@ -202,6 +418,17 @@ export interface AppSettings {
   notifications: boolean;
 }
 
+export interface PathPolicyUserRules {
+  protected_paths: string[];
+  allowed_roots: string[];
+}
+
+export interface PathPolicyInfo {
+  protected_trees: string[];
+  protected_paths: string[];
+  user: PathPolicyUserRules;
+}
+
 // Update types
 export interface UpdateInfo {
   version: string;